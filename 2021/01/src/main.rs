@@ -1,29 +1,95 @@
 use std::str::FromStr;
 
-fn main() {
-    let mut increasing = 0;
-    let values = include_str!("../input")
+use anyhow::{Context, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng},
+    runner::Runner,
+};
+
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+fn depth(line: &str) -> Result<usize> {
+    usize::from_str(line).with_context(|| format!("invalid depth {line:?}"))
+}
+
+fn depths(input: &str) -> Result<Vec<usize>> {
+    input
         .lines()
-        .map(|a| usize::from_str(a).unwrap());
-    #[cfg(feature = "first")]
-    let mut values = values.peekable();
-    #[cfg(not(feature = "first"))]
-    let values: Vec<_> = values.collect();
-
-    #[cfg(feature = "first")]
-    while let (Some(last), Some(&current)) = (values.next(), values.peek()) {
-        if last < current {
-            increasing += 1;
-        }
+        .enumerate()
+        .map(|(idx, line)| depth(line).with_context(|| format!("line {}", idx + 1)))
+        .collect()
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    check::lines(input, |line| depth(line).map(drop))
+}
+
+/// `size` depths drifting downwards.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let mut depth: usize = rng.gen_range(100..200);
+    let mut input = String::new();
+    for _ in 0..size {
+        input += &format!("{depth}\n");
+        depth = depth.saturating_add_signed(rng.gen_range(-10..=30));
+    }
+    input
+}
+
+/// Counts how often the sum of a sliding window of `size` depths increases.
+fn shared(input: &str, size: usize) -> Result<usize> {
+    let depths = depths(input)?;
+    // Consecutive windows share all but their first and last depth.
+    Ok(depths
+        .iter()
+        .zip(depths.iter().skip(size))
+        .filter(|(first, last)| first < last)
+        .count())
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    shared(input, 1).map(Answer::from)
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    shared(input, 3).map(Answer::from)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+        199
+        200
+        208
+        210
+        200
+        207
+        240
+        269
+        260
+        263
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "7";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
     }
 
-    for i in 1..(values.len() - 2) {
-        if values[(i - 1)..(i + 2)].iter().sum::<usize>()
-            < values[(i)..(i + 3)].iter().sum::<usize>()
-        {
-            increasing += 1;
-        }
+    #[test]
+    fn two() {
+        let output = "5";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
 
-    dbg!(increasing);
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
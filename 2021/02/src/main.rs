@@ -1,43 +1,140 @@
-#[cfg(feature = "one")]
-fn main() {
-    let mut x = 0;
-    let mut y = 0;
-    for (direction, value) in include_str!("input")
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Error, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng, SliceRandom},
+    runner::Runner,
+};
+
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+enum Command {
+    Forward(i64),
+    Down(i64),
+    Up(i64),
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (direction, value) = s
+            .split_once(' ')
+            .context("command format is `<direction> <value>`")?;
+        let value = value.parse().context("parsing value")?;
+        Ok(match direction {
+            "forward" => Self::Forward(value),
+            "down" => Self::Down(value),
+            "up" => Self::Up(value),
+            other => bail!("unknown direction {other:?}"),
+        })
+    }
+}
+
+fn commands(input: &str) -> Result<Vec<Command>> {
+    input
         .lines()
-        .map(|s| s.split_once(' ').unwrap())
-    {
-        let value: usize = value.parse().unwrap();
-        match direction {
-            "forward" => x += value,
-            "down" => y += value,
-            "up" => y -= value,
-            _ => unreachable!(),
+        .enumerate()
+        .map(|(idx, line)| {
+            line.parse()
+                .with_context(|| format!("line {}: {line:?}", idx + 1))
+        })
+        .collect()
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    check::lines(input, |line| Command::from_str(line).map(drop))
+}
+
+/// `size` commands moving at most 9 units each.
+fn generate(rng: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let direction = ["forward", "down", "up"].choose(rng).unwrap();
+            format!("{direction} {}\n", rng.gen_range(1..10))
+        })
+        .collect()
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    let (mut x, mut y) = (0i64, 0i64);
+    for (idx, command) in commands(input)?.into_iter().enumerate() {
+        let overflow = || format!("line {}: position overflowed", idx + 1);
+        match command {
+            Command::Forward(value) => x = x.checked_add(value).with_context(overflow)?,
+            Command::Down(value) => y = y.checked_add(value).with_context(overflow)?,
+            Command::Up(value) => y = y.checked_sub(value).with_context(overflow)?,
         }
     }
-    dbg!(x * y);
-    println!("Hello, world!");
+    x.checked_mul(y)
+        .context("the product of the position overflowed")
+        .map(Answer::from)
 }
 
-#[cfg(not(feature = "one"))]
-fn main() {
-    let mut x = 0;
-    let mut y = 0;
-    let mut aim = 0;
-    for (direction, value) in include_str!("input")
-        .lines()
-        .map(|s| s.split_once(' ').unwrap())
-    {
-        let value: usize = value.parse().unwrap();
-        match direction {
-            "forward" => {
-                x += value;
-                y += aim * value
+pub fn two(input: &str) -> Result<Answer> {
+    let (mut x, mut y, mut aim) = (0i64, 0i64, 0i64);
+    for (idx, command) in commands(input)?.into_iter().enumerate() {
+        let overflow = || format!("line {}: position overflowed", idx + 1);
+        match command {
+            Command::Forward(value) => {
+                x = x.checked_add(value).with_context(overflow)?;
+                y = aim
+                    .checked_mul(value)
+                    .and_then(|depth| y.checked_add(depth))
+                    .with_context(overflow)?;
             }
-            "down" => aim += value,
-            "up" => aim -= value,
-            _ => unreachable!(),
+            Command::Down(value) => aim = aim.checked_add(value).with_context(overflow)?,
+            Command::Up(value) => aim = aim.checked_sub(value).with_context(overflow)?,
         }
     }
-    dbg!(x * y);
-    println!("Hello, world!");
+    x.checked_mul(y)
+        .context("the product of the position overflowed")
+        .map(Answer::from)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+        forward 5
+        down 5
+        forward 8
+        up 3
+        down 8
+        forward 2
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "150";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "900";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "forward 9223372036854775807\ndown 9223372036854775807\n";
+        let error = super::one(input).unwrap_err();
+        assert_eq!(error.to_string(), "the product of the position overflowed");
+        let error = super::two(&format!("{input}forward 2\n")).unwrap_err();
+        assert_eq!(error.to_string(), "line 3: position overflowed");
+        let error = super::one("up 9223372036854775807\nup 2\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: position overflowed");
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
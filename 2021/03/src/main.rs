@@ -1,84 +1,114 @@
-#[cfg(one)]
-fn main() {
-    const LINE_LEN: usize = 12;
-    let input = include_str!("input")
-        .lines()
-        .fold([(0, 0); LINE_LEN], |mut acc, s| {
-            s.chars().enumerate().for_each(|(i, c)| {
-                if c == '1' {
-                    acc[i].1 += 1
-                } else {
-                    acc[i].0 += 1
-                }
-            });
-            acc
-        })
-        .into_iter()
-        .rev()
-        .enumerate()
-        .fold((0, 0), |acc, (i, value)| {
-            let value = if value.0 > value.1 { (1, 0) } else { (0, 1) };
-            (acc.0 + (value.0 << i), acc.1 + (value.1 << i))
-        });
+use anyhow::{Context, Result};
+use aoc::{
+    answer::Answer,
+    bits::{BitReport, ColumnStats},
+    check::{self, Problem},
+    generate::{Random, Rng, SliceRandom},
+    runner::Runner,
+};
 
-    dbg!(input.0 * input.1);
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    let width = input.lines().next().map_or(0, str::len);
+    check::lines(input, |line| BitReport::parse_number(line, width).map(drop))
+}
+
+/// `size` distinct numbers for which both ratings are unique.
+///
+/// The ratings only fail to be unique if at some point all remaining numbers
+/// share a bit, so the numbers are split into two non-empty groups by every
+/// column until each group holds a single number.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let size = size.max(2);
+    let width = (usize::BITS - size.leading_zeros()) as usize + 1;
+    let mut numbers = Vec::with_capacity(size);
+    split(rng, &mut numbers, 0, width, size);
+    numbers.shuffle(rng);
+    numbers
+        .iter()
+        .map(|number| format!("{number:0width$b}\n"))
+        .collect()
+}
+
+/// Pushes `count` distinct numbers starting with `prefix` and `bits` more
+/// bits, splitting them by every bit while there are two or more.
+fn split(rng: &mut Random, numbers: &mut Vec<u64>, prefix: u64, bits: usize, count: usize) {
+    if count == 1 {
+        numbers.push(prefix << bits | rng.gen_range(0..1 << bits));
+        return;
+    }
+    let half = 1 << (bits - 1);
+    let zeros = rng.gen_range(count.saturating_sub(half).max(1)..=(count - 1).min(half));
+    split(rng, numbers, prefix << 1, bits - 1, zeros);
+    split(rng, numbers, prefix << 1 | 1, bits - 1, count - zeros);
 }
 
-fn bis_to_num(bits: &[u32]) -> u32 {
-    bits.iter()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (i, value)| acc + (value << i))
+pub fn one(input: &str) -> Result<Answer> {
+    let report: BitReport = input.parse()?;
+    u64::checked_mul(report.gamma(), report.epsilon())
+        .context("power consumption overflowed")
+        .map(Answer::from)
 }
 
-// #[cfg(two)]
-fn main() {
-    let numbers: Vec<Vec<_>> = include_str!("input")
-        .lines()
-        .map(|s| s.chars().map(|c| if c == '1' { 1 } else { 0 }).collect())
-        .collect();
+pub fn two(input: &str) -> Result<Answer> {
+    let report: BitReport = input.parse()?;
+    let oxygen = report
+        .filter_by(ColumnStats::most_common)
+        .context("oxygen generator rating")?;
+    let co2 = report
+        .filter_by(ColumnStats::least_common)
+        .context("CO2 scrubber rating")?;
+    u64::checked_mul(oxygen, co2)
+        .context("life support rating overflowed")
+        .map(Answer::from)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+        00100
+        11110
+        10110
+        10111
+        10101
+        01111
+        00111
+        11100
+        10000
+        11001
+        00010
+        01010
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "198";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "230";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
 
-    let mut numbers_o = numbers.clone();
-    let mut numbers_c = numbers;
-    for i in 0..numbers_o[0].len() {
-        let common = numbers_o.iter().fold((0, 0), |mut acc, v| {
-            if v[i] == 0 {
-                acc.0 += 1;
-            } else {
-                acc.1 += 1;
-            }
-            acc
-        });
-        numbers_o = numbers_o
-            .into_iter()
-            .filter(|v| {
-                if common.0 > common.1 {
-                    v[i] == 0
-                } else {
-                    v[i] == 1
-                }
-            })
-            .collect();
-        let common = numbers_c.iter().fold((0, 0), |mut acc, v| {
-            if v[i] == 0 {
-                acc.0 += 1;
-            } else {
-                acc.1 += 1;
-            }
-            acc
-        });
-        if numbers_c.len() > 1 {
-            numbers_c = numbers_c
-                .into_iter()
-                .filter(|v| {
-                    if common.0 <= common.1 {
-                        v[i] == 0
-                    } else {
-                        v[i] == 1
-                    }
-                })
-                .collect();
-        }
+    #[test]
+    fn wide() {
+        let a = "10".repeat(20);
+        let b = "01".repeat(20);
+        let input = format!("{a}\n{a}\n{b}\n");
+        let err = super::one(&input).unwrap_err();
+        assert_eq!(err.to_string(), "power consumption overflowed");
     }
-    dbg!(bis_to_num(&numbers_o[0]) * bis_to_num(&numbers_c[0]));
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
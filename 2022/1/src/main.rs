@@ -1,63 +1,141 @@
 #![doc = include_str!("../README.md")]
-use std::{cmp::Reverse, env, str::FromStr};
+use std::str::FromStr;
 
-use anyhow::{bail, Error, Result};
-use itertools::Itertools;
+use anyhow::{ensure, Context, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng},
+    groups::groups,
+    runner::Runner,
+    top::TopN,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
+const INPUT: &str = include_str!("../input");
+
+/// `--top <n>` sums up the `n` elves carrying the most instead of the 1 or 3
+/// of the part.
+fn main() -> Result<()> {
+    let mut runner = Runner::from_env()?;
+    let Some(n) = runner.value::<usize>("--top")? else {
+        return runner.generator(generate).run(INPUT, validate, one, two);
+    };
+    let answer = |input: &str| top(input, n).map(Answer::from);
+    runner
+        .custom()
+        .generator(generate)
+        .run(INPUT, validate, answer, answer)
 }
-impl FromStr for Part {
-    type Err = Error;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
+/// The total calories carried by each elf.
+fn calories(input: &str) -> impl Iterator<Item = Result<u32>> + '_ {
+    groups(input).map(|group| {
+        group.lines().try_fold(0, |aggr, (line, text)| {
+            let value = u32::from_str(text)
+                .with_context(|| format!("line {line}: invalid calories {text:?}"))?;
+            u32::checked_add(aggr, value)
+                .with_context(|| format!("line {line}: total calories overflowed"))
         })
+    })
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check::lines(input, |line| {
+        if !line.is_empty() {
+            u32::from_str(line).with_context(|| format!("invalid calories {line:?}"))?;
+        }
+        Ok(())
+    });
+    let elves = groups(input).count();
+    if elves < 3 {
+        problems.push(Problem::new(
+            None,
+            format_args!("only {elves} elves, expected at least 3"),
+        ));
     }
+    problems
 }
-const INPUT: &str = include_str!("../input");
 
-fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    let mut output = INPUT.lines().peekable().batching(|it| {
-        it.peek().is_some().then(|| {
-            let value = it
-                .peeking_take_while(|line| !line.is_empty())
-                .map(u32::from_str)
-                .try_fold(0, |aggr, value| value.map(|value| aggr + value));
-            it.next();
-            value
+/// `size` elves, but at least 3, carrying up to 10 snacks each.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let elves: Vec<String> = (0..size.max(3))
+        .map(|_| {
+            (0..rng.gen_range(1..=10))
+                .map(|_| format!("{}\n", rng.gen_range(1000..=60000)))
+                .collect()
         })
-    });
-    let output = match part {
-        Part::One => output.try_fold(0, |aggr, value| value.map(|value| aggr.max(value)))?,
-        Part::Two => output
-            .try_fold(vec![0u32; 3], |mut aggr, value| {
-                value.map(|value| {
-                    aggr.push(value);
-                    aggr.sort_by_key(|v| Reverse(*v));
-                    aggr.pop();
-                    aggr
-                })
-            })?
-            .iter()
-            .sum(),
-    };
+        .collect();
+    elves.join("\n")
+}
+
+/// The sum of the calories carried by the `n` elves carrying the most.
+fn top(input: &str, n: usize) -> Result<u32> {
+    let mut top = TopN::new(n);
+    for calories in calories(input) {
+        top.push(calories?);
+    }
+    ensure!(top.len() == n, "there are fewer than {n} elves");
+    top.into_iter()
+        .try_fold(0, u32::checked_add)
+        .context("total calories overflowed")
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    top(input, 1).map(Answer::from)
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    top(input, 3).map(Answer::from)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+        1000
+        2000
+        3000
+
+        4000
+
+        5000
+        6000
+
+        7000
+        8000
+        9000
+
+        10000
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "24000";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "45000";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn top() {
+        assert_eq!(super::top(INPUT, 2).unwrap(), 35000);
+        assert_eq!(super::top(INPUT, 5).unwrap(), 55000);
+        let error = super::top(INPUT, 6).unwrap_err();
+        assert_eq!(error.to_string(), "there are fewer than 6 elves");
+    }
+
+    #[test]
+    fn malformed() {
+        let error = super::top("1000\n\n2O00\n", 1).unwrap_err();
+        assert_eq!(error.to_string(), "line 3: invalid calories \"2O00\"");
+        let error = super::top("4000000000\n\n4000000000\n", 2).unwrap_err();
+        assert_eq!(error.to_string(), "total calories overflowed");
+    }
 
-    println!("{output}");
-    Ok(())
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
@@ -1,64 +1,89 @@
 #![doc = include_str!("../README.md")]
-use std::{env, fmt::Display, str::FromStr};
+use std::str::FromStr;
 
-use anyhow::{bail, Context, Error, Result};
+use anyhow::{Context, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng},
+    parse::{self, Cursor},
+    runner::Runner,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
-}
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
-}
 const INPUT: &str = include_str!("../input");
 
 fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
 
-    eprintln!("=== Solving Part {part:?} ===");
+/// The cycles an instruction takes.
+fn instruction(c: &mut Cursor) -> parse::Result<usize> {
+    if c.eat("noop") {
+        Ok(1)
+    } else {
+        c.tag("addx ")?;
+        c.signed::<i32>()?;
+        Ok(2)
+    }
+}
 
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check::parse_lines(input, instruction);
+    let cycles: usize = input
+        .lines()
+        .filter_map(|line| parse::parse(line, instruction).ok())
+        .sum();
+    if cycles < 240 {
+        problems.push(Problem::new(
+            None,
+            format_args!("program runs for {cycles} cycles, 240 are needed"),
+        ));
     }
+    problems
+}
 
-    Ok(())
+/// A program of `size` instructions, extended until it runs for the 240
+/// cycles of the screen.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let mut program = String::new();
+    let mut cycles = 0;
+    let mut instructions = 0;
+    while instructions < size || cycles < 240 {
+        if rng.gen_bool(0.3) {
+            program += "noop\n";
+            cycles += 1;
+        } else {
+            program += &format!("addx {}\n", rng.gen_range(-20..=20));
+            cycles += 2;
+        }
+        instructions += 1;
+    }
+    program
 }
 
-fn one(mut input: &str) -> Result<impl Display> {
+pub fn one(mut input: &str) -> Result<Answer> {
     let mut x = 1;
-    let mut signal = 0;
+    let mut signal = 0i32;
     for clk in 1..=220 {
         let token;
         (token, input) = input
             .split_once(char::is_whitespace)
             .context("should have enough instructions")?;
         if clk % 40 == 20 {
-            signal += clk * x;
+            signal = i32::checked_mul(clk, x)
+                .and_then(|strength| signal.checked_add(strength))
+                .context("signal strength overflowed")?;
         }
         if let Ok(number) = i32::from_str(token) {
-            x += number;
+            x = x.checked_add(number).context("register X overflowed")?;
         }
     }
-    Ok(signal)
+    Ok(signal.into())
 }
 
-fn two(mut input: &str) -> Result<impl Display> {
+pub fn two(mut input: &str) -> Result<Answer> {
     let mut x = 1i32;
     let mut output = String::new();
     for clk in 1..=240 {
@@ -72,13 +97,13 @@ fn two(mut input: &str) -> Result<impl Display> {
             .split_once(char::is_whitespace)
             .context("should have enough instructions")?;
         if let Ok(number) = i32::from_str(token) {
-            x += number;
+            x = x.checked_add(number).context("register X overflowed")?;
         }
         if clk % 40 == 0 {
             output.push('\n');
         }
     }
-    Ok(output)
+    Ok(Answer::grid(&output))
 }
 
 #[cfg(test)]
@@ -234,6 +259,20 @@ mod test {
         noop
     "#};
 
+    #[test]
+    fn validate() {
+        assert_eq!(super::validate(INPUT), []);
+        let problems = super::validate("noop\naddx x\nmul 2\n");
+        assert_eq!(
+            problems.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "line 2: column 6: expected number, found 'x'",
+                "line 3: column 1: expected \"addx \", found 'm'",
+                "program runs for 1 cycles, 240 are needed"
+            ]
+        );
+    }
+
     #[test]
     fn one() {
         let output = "13140";
@@ -252,4 +291,14 @@ mod test {
             "};
         assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    #[test]
+    fn malformed() {
+        let error = super::one("addx 2147483647\naddx 1\n".repeat(60).as_str())
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "register X overflowed");
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
@@ -1,134 +1,188 @@
-#![feature(box_syntax)]
-#![allow(clippy::type_complexity)]
 #![doc = include_str!("../README.md")]
-use std::{env, fmt::Display, mem, ops::Mul, str::FromStr};
+use std::mem;
 
-use anyhow::{bail, Context, Error, Result};
+use anyhow::{ensure, Context, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng, SliceRandom},
+    parse::{self, Cursor},
+    profile,
+    runner::Runner,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
-}
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
-}
 const INPUT: &str = include_str!("../input");
 
 fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
-    }
-
-    Ok(())
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
 }
 
 struct Monkey {
     items: Vec<u64>,
-    op: Box<dyn Fn(&mut u64)>,
-    test: (Box<dyn Fn(u64) -> usize>, u64),
+    /// The new worry level, `None` if it does not fit into a `u64`.
+    op: Box<dyn Fn(u64) -> Option<u64>>,
+    divisor: u64,
+    /// The monkeys to throw to if the item is or is not divisible.
+    targets: (usize, usize),
     interactions: u64,
 }
 
-impl FromStr for Monkey {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut s = s.lines();
-        s.next();
+impl Monkey {
+    fn parse(cursor: &mut Cursor) -> parse::Result<Self> {
+        cursor.field("Monkey ", |c| {
+            c.unsigned::<usize>()?;
+            c.tag(":")
+        })?;
+        let items = cursor.field("  Starting items: ", |c| {
+            c.separated(", ", Cursor::unsigned)
+        })?;
+        let op = cursor.field("  Operation: new = old ", |c| {
+            let start = *c;
+            let op = c.word()?;
+            c.tag(" ")?;
+            let op: Box<dyn Fn(u64) -> Option<u64>> = if c.eat("old") {
+                match op {
+                    "*" => Box::new(move |v| v.checked_mul(v)),
+                    "+" => Box::new(move |v| v.checked_add(v)),
+                    op => return Err(start.error(format_args!("unsupported operation {op:?}"))),
+                }
+            } else {
+                let value = c.unsigned::<u64>()?;
+                match op {
+                    "*" => Box::new(move |v| v.checked_mul(value)),
+                    "+" => Box::new(move |v| v.checked_add(value)),
+                    op => return Err(start.error(format_args!("unsupported operation {op:?}"))),
+                }
+            };
+            Ok(op)
+        })?;
+        let test = *cursor;
+        let divisor = cursor.field("  Test: divisible by ", Cursor::unsigned)?;
+        if divisor == 0 {
+            return Err(test.error("cannot test for divisibility by 0"));
+        }
+        let t = cursor.field("    If true: throw to monkey ", Cursor::unsigned)?;
+        let f = cursor.field("    If false: throw to monkey ", Cursor::unsigned)?;
         Ok(Monkey {
-            items: s
-                .next()
-                .and_then(|s| s.strip_prefix("  Starting items: "))
-                .context("has starting items")?
-                .split(", ")
-                .map(u64::from_str)
-                .collect::<Result<_, _>>()
-                .context("starting items")?,
-            op: s
-                .next()
-                .and_then(|s| s.strip_prefix("  Operation: new = old "))
-                .and_then(|s| s.split_once(' '))
-                .context("has operation")
-                .and_then(|(op, value)| -> Result<Box<dyn Fn(&mut u64)>> {
-                    Ok(if value == "old" {
-                        match op {
-                            "*" => box move |v| *v *= *v,
-                            "+" => box move |v| *v += *v,
-                            op => bail!("unsupported operation {op:?}"),
-                        }
-                    } else {
-                        let value = u64::from_str(value)?;
-                        match op {
-                            "*" => box move |v| *v *= value,
-                            "+" => box move |v| *v += value,
-                            op => bail!("unsupported operation {op:?}"),
-                        }
-                    })
-                })
-                .context("operation")?,
-            test: s
-                .next()
-                .and_then(|s| s.strip_prefix("  Test: divisible by "))
-                .context("has test")
-                .and_then(|divisor| u64::from_str(divisor).map_err(From::from))
-                .and_then(|divisor| -> Result<(Box<dyn Fn(u64) -> usize>, u64)> {
-                    let t = s
-                        .next()
-                        .and_then(|s| s.strip_prefix("    If true: throw to monkey "))
-                        .context("has true case")
-                        .and_then(|v| usize::from_str(v).map_err(From::from))?;
-                    let f = s
-                        .next()
-                        .and_then(|s| s.strip_prefix("    If false: throw to monkey "))
-                        .context("has true case")
-                        .and_then(|v| usize::from_str(v).map_err(From::from))?;
-                    Ok((box move |v| if v % divisor == 0 { t } else { f }, divisor))
-                })
-                .context("test")?,
+            items,
+            op,
+            divisor,
+            targets: (t, f),
             interactions: 0,
         })
     }
+
+    fn target(&self, item: u64) -> usize {
+        if item.is_multiple_of(self.divisor) {
+            self.targets.0
+        } else {
+            self.targets.1
+        }
+    }
+}
+
+fn check_targets(monkeys: &[Monkey]) -> Result<()> {
+    for monkey in monkeys {
+        for target in [monkey.targets.0, monkey.targets.1] {
+            ensure!(
+                target < monkeys.len(),
+                "there is no monkey {target} to throw to"
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check::blocks(input, |block| parse::parse(block, Monkey::parse).map(drop));
+    if let Ok(monkeys) = parse::parse(input, |cursor| cursor.blocks(Monkey::parse)) {
+        problems.extend(
+            check_targets(&monkeys)
+                .err()
+                .map(|error| Problem::new(None, error)),
+        );
+    }
+    problems
+}
+
+/// `size` monkeys, clamped to between 3 and 9 so every monkey can test for a
+/// distinct prime without the reduced worry levels overflowing.
+///
+/// Like the real inputs, exactly one monkey squares the worry level, and the
+/// exact worry levels of part one fit into a `u64`.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let count = size.clamp(3, 9);
+    loop {
+        let mut primes = vec![2, 3, 5, 7, 11, 13, 17, 19, 23];
+        primes.shuffle(rng);
+        let squaring = rng.gen_range(0..count);
+        let monkeys: Vec<String> = (0..count)
+            .map(|idx| {
+                let items: Vec<String> = (0..rng.gen_range(1..=8))
+                    .map(|_| rng.gen_range(50..100).to_string())
+                    .collect();
+                let operation = if idx == squaring {
+                    "old * old".to_string()
+                } else if rng.gen_bool(0.3) {
+                    format!("old * {}", rng.gen_range(2..20))
+                } else {
+                    format!("old + {}", rng.gen_range(1..9))
+                };
+                let on_true = (idx + rng.gen_range(1..count)) % count;
+                let on_false = loop {
+                    let target = (idx + rng.gen_range(1..count)) % count;
+                    if target != on_true {
+                        break target;
+                    }
+                };
+                format!(
+                    "Monkey {idx}:\n  Starting items: {}\n  Operation: new = {operation}\n  \
+                     Test: divisible by {}\n    If true: throw to monkey {on_true}\n    \
+                     If false: throw to monkey {on_false}\n",
+                    items.join(", "),
+                    primes[idx]
+                )
+            })
+            .collect();
+        let input = monkeys.join("\n");
+        if one(&input).is_ok() {
+            return input;
+        }
+    }
 }
 
-fn shared(input: &str, rounds: u64, devisor: u64) -> Result<impl Display> {
-    let mut input = input
-        .split("\n\n")
-        .map(Monkey::from_str)
-        .collect::<Result<Vec<_>>>()?;
-    let size_devisor = input.iter().fold(1, |aggr, monkey| aggr * monkey.test.1);
+fn shared(input: &str, rounds: u64, devisor: u64) -> Result<u64> {
+    let parsing = profile::span("parse");
+    let mut input = parse::parse(input, |cursor| cursor.blocks(Monkey::parse))?;
+    check_targets(&input)?;
+    drop(parsing);
+    // Only whether items are divisible matters, which reducing modulo the
+    // product of all divisors preserves. Dividing the worry level does not
+    // commute with reducing it though, so then the exact levels are needed.
+    let modulus = input
+        .iter()
+        .try_fold(1u64, |aggr, monkey| aggr.checked_mul(monkey.divisor))
+        .context("the product of the divisors overflowed")?;
+    let simulation = profile::span("simulate");
     for _ in 0..rounds {
         for monkey in 0..input.len() {
             let items = mem::take(&mut input[monkey].items);
-            for mut item in items.into_iter().rev() {
-                (input[monkey].op)(&mut item);
-                item /= devisor;
-                item %= size_devisor;
+            for item in items.into_iter().rev() {
+                let item = (input[monkey].op)(item).context("worry level overflowed")?;
+                let item = if devisor == 1 {
+                    item % modulus
+                } else {
+                    item / devisor
+                };
                 input[monkey].interactions += 1;
-                let monkey = (input[monkey].test.0)(item);
+                let monkey = input[monkey].target(item);
                 input[monkey].items.push(item);
             }
         }
     }
+    drop(simulation);
     input
         .iter()
         .fold([0, 0], |aggr, curr| {
@@ -137,21 +191,26 @@ fn shared(input: &str, rounds: u64, devisor: u64) -> Result<impl Display> {
             [aggr[1], aggr[2]]
         })
         .into_iter()
-        .reduce(Mul::mul)
-        .context("there are more than 2 monkeys")
+        .try_fold(1u64, u64::checked_mul)
+        .context("monkey business overflowed")
 }
 
-fn one(input: &str) -> Result<impl Display> {
-    shared(input, 20, 3)
+pub fn one(input: &str) -> Result<Answer> {
+    shared(input, 20, 3).map(Answer::from)
 }
 
-fn two(input: &str) -> Result<impl Display> {
-    shared(input, 10000, 1)
+pub fn two(input: &str) -> Result<Answer> {
+    shared(input, 10000, 1).map(Answer::from)
 }
 
 #[cfg(test)]
 mod test {
+    use std::mem;
+
+    use aoc::{check::Problem, generate::generate};
     use indoc::indoc;
+    use num_bigint::BigUint;
+    use proptest::{prop_assert_eq, proptest, sample::select};
 
     const INPUT: &str = indoc! {r#"
         Monkey 0:
@@ -194,4 +253,135 @@ mod test {
         let output = "2713310158";
         assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    #[test]
+    fn malformed() {
+        let input = INPUT.replace("Operation: new = old + 6", "Operation: new = old - 6");
+        let error = super::one(&input).err().unwrap();
+        assert_eq!(error.to_string(), "10:24: unsupported operation \"-\"");
+        let input = INPUT.replace("If false: throw to monkey 1", "If false: throw to monkey 4");
+        assert!(super::one(&input).is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        let input = INPUT
+            .split("\n\n")
+            .map(|monkey| {
+                let divisor = monkey.lines().nth(3).unwrap();
+                monkey.replace(divisor, "  Test: divisible by 4294967311")
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let error = super::two(&input).unwrap_err();
+        assert_eq!(error.to_string(), "the product of the divisors overflowed");
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(super::validate(INPUT), []);
+        let input = INPUT
+            .replace("Operation: new = old + 6", "Operation: new = old - 6")
+            .replace("divisible by 13", "divisible by 0");
+        assert_eq!(
+            super::validate(&input)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "line 10: column 24: unsupported operation \"-\"",
+                "line 18: column 1: cannot test for divisibility by 0"
+            ]
+        );
+        let input = INPUT.replace("If false: throw to monkey 1", "If false: throw to monkey 4");
+        assert_eq!(
+            super::validate(&input),
+            [Problem::new(None, "there is no monkey 4 to throw to")]
+        );
+    }
+
+    aoc::stress_test!(
+        super::generate,
+        super::validate,
+        super::one,
+        super::two,
+        0..5,
+        10
+    );
+
+    /// Simulates with exact worry levels instead of reducing them modulo the
+    /// product of the divisors.
+    fn reference(input: &str, rounds: u64, devisor: u32) -> u64 {
+        struct Monkey {
+            items: Vec<BigUint>,
+            multiply: bool,
+            /// `None` if the operation uses the old value twice.
+            operand: Option<u32>,
+            divisor: u32,
+            targets: [usize; 2],
+            interactions: u64,
+        }
+
+        let mut monkeys: Vec<_> = input
+            .split("\n\n")
+            .map(|block| {
+                let lines: Vec<_> = block.lines().collect();
+                let last = |line: usize| lines[line].rsplit(' ').next().unwrap();
+                let (_, items) = lines[1].split_once(": ").unwrap();
+                let operation: Vec<_> = lines[2].split_whitespace().collect();
+                Monkey {
+                    items: items
+                        .split(", ")
+                        .map(|item| item.parse().unwrap())
+                        .collect(),
+                    multiply: operation[4] == "*",
+                    operand: operation[5].parse().ok(),
+                    divisor: last(3).parse().unwrap(),
+                    targets: [last(4).parse().unwrap(), last(5).parse().unwrap()],
+                    interactions: 0,
+                }
+            })
+            .collect();
+        for _ in 0..rounds {
+            for idx in 0..monkeys.len() {
+                for item in mem::take(&mut monkeys[idx].items) {
+                    let monkey = &mut monkeys[idx];
+                    monkey.interactions += 1;
+                    let operand = monkey.operand.map_or_else(|| item.clone(), BigUint::from);
+                    let item = if monkey.multiply {
+                        item * operand
+                    } else {
+                        item + operand
+                    } / devisor;
+                    let divisible = &item % monkey.divisor == BigUint::default();
+                    let target = monkey.targets[usize::from(!divisible)];
+                    monkeys[target].items.push(item);
+                }
+            }
+        }
+        let mut interactions: Vec<_> = monkeys.iter().map(|monkey| monkey.interactions).collect();
+        interactions.sort_unstable_by(|a, b| b.cmp(a));
+        interactions[0] * interactions[1]
+    }
+
+    #[test]
+    fn reference_matches_example() {
+        assert_eq!(reference(INPUT, 20, 3), 10605);
+    }
+
+    proptest! {
+        #[test]
+        fn reduced_worry(
+            seed: u64,
+            size in 3usize..=9,
+            rounds in 1u64..=10,
+            devisor in select(vec![1, 3]),
+        ) {
+            let input = generate(super::generate, seed, size);
+            prop_assert_eq!(
+                super::shared(&input, rounds, devisor.into()).unwrap().to_string(),
+                reference(&input, rounds, devisor).to_string()
+            );
+        }
+    }
 }
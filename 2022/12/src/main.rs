@@ -1,49 +1,93 @@
-#![feature(is_some_and)]
 #![doc = include_str!("../README.md")]
-use std::{env, fmt::Display, str::FromStr};
-
-use anyhow::{bail, Context, Error, Result};
+use anyhow::{ensure, Context, Result};
+use aoc::{
+    answer::Answer,
+    check::Problem,
+    generate::{Random, Rng, SliceRandom},
+    runner::Runner,
+};
 use pathfinding::prelude::astar;
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
-}
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
-}
 const INPUT: &str = include_str!("../input");
 
 fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
 
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let width = input.lines().next().map_or(0, str::len);
+    if width == 0 {
+        problems.push(Problem::new(None, "heightmap is empty"));
+    }
+    for (idx, line) in input.lines().enumerate() {
+        let line_number = idx + 1;
+        if line.len() != width {
+            problems.push(Problem::new(
+                line_number,
+                format_args!("expected {width} squares, found {}", line.len()),
+            ));
+        }
+        if let Some((column, c)) = line
+            .chars()
+            .enumerate()
+            .find(|&(_, c)| !matches!(c, 'a'..='z' | 'S' | 'E'))
+        {
+            problems.push(Problem::new(
+                line_number,
+                format_args!("invalid elevation {c:?} in column {}", column + 1),
+            ));
+        }
+    }
+    for marker in ['S', 'E'] {
+        let count = input.matches(marker).count();
+        if count != 1 {
+            problems.push(Problem::new(
+                None,
+                format_args!("expected exactly one {marker:?}, found {count}"),
+            ));
+        }
     }
+    problems
+}
 
-    Ok(())
+/// A heightmap of `size` rows, but at least 3, and twice as many columns.
+///
+/// A path from `S` at the left to `E` at the right edge stays at `a` and then
+/// climbs one elevation per step, all other squares are random.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let height = size.max(3);
+    // The path needs at least 27 squares for its first step to be an `a`.
+    let width = (2 * height).max(27);
+    let mut map: Vec<Vec<u8>> = (0..height)
+        .map(|_| (0..width).map(|_| rng.gen_range(b'a'..=b'z')).collect())
+        .collect();
+    let (start, end) = (rng.gen_range(0..height), rng.gen_range(0..height));
+    let mut steps = vec![(1, 0); width - 1];
+    steps.extend(vec![(0, 1); start.abs_diff(end)]);
+    steps.shuffle(rng);
+    let len = steps.len() + 1;
+    let (mut x, mut y) = (0, start);
+    map[y][x] = b'S';
+    for (idx, (dx, dy)) in steps.into_iter().enumerate() {
+        x += dx;
+        y = if start < end { y + dy } else { y - dy };
+        map[y][x] = b'z' - (len - 2 - idx).min(25) as u8;
+    }
+    map[y][x] = b'E';
+    map.into_iter()
+        .map(|row| String::from_utf8(row).expect("map is ascii") + "\n")
+        .collect()
 }
 
-fn shared(input: &str, start: char, target: char, part_2: bool) -> Result<impl Display> {
+fn shared(input: &str, start: char, target: char, part_2: bool) -> Result<usize> {
     let width = input.find('\n').context("there is a linefeed")?;
+    ensure!(
+        input.lines().all(|line| line.len() == width),
+        "rows differ in width"
+    );
     let pos = |x, y| x + y * (width + 1);
     let s = input.find(start).context("there is a start")?;
     let e = input.find(target).context("there is a target")?;
@@ -93,12 +137,12 @@ fn shared(input: &str, start: char, target: char, part_2: bool) -> Result<impl D
     .1)
 }
 
-fn one(input: &str) -> Result<impl Display> {
-    shared(input, 'S', 'E', false)
+pub fn one(input: &str) -> Result<Answer> {
+    shared(input, 'S', 'E', false).map(Answer::from)
 }
 
-fn two(input: &str) -> Result<impl Display> {
-    shared(input, 'E', 'a', true)
+pub fn two(input: &str) -> Result<Answer> {
+    shared(input, 'E', 'a', true).map(Answer::from)
 }
 
 #[cfg(test)]
@@ -113,6 +157,20 @@ mod test {
         abdefghi
     "#};
 
+    #[test]
+    fn validate() {
+        assert_eq!(super::validate(INPUT), []);
+        let problems = super::validate("Sab\nc1\nSEd\n");
+        assert_eq!(
+            problems.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "line 2: expected 3 squares, found 2",
+                "line 2: invalid elevation '1' in column 2",
+                "expected exactly one 'S', found 2"
+            ]
+        );
+    }
+
     #[test]
     fn one() {
         let output = "31";
@@ -124,4 +182,18 @@ mod test {
         let output = "29";
         assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    #[test]
+    fn malformed() {
+        assert!(super::one("SbcdefghijklmnopqrstuvwxyE\nab\n").is_err());
+    }
+
+    aoc::stress_test!(
+        super::generate,
+        super::validate,
+        super::one,
+        super::two,
+        0..20,
+        10
+    );
 }
@@ -1,66 +1,249 @@
 #![doc = include_str!("../README.md")]
-use std::{env, str::FromStr};
+use std::str::FromStr;
 
 use anyhow::{bail, Error, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, SliceRandom},
+    parse::{self, Cursor},
+    runner::Runner,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Shape {
+    /// The shape this shape defeats.
+    fn beats(self) -> Self {
+        match self {
+            Self::Rock => Self::Scissors,
+            Self::Paper => Self::Rock,
+            Self::Scissors => Self::Paper,
+        }
+    }
+
+    /// The shape that defeats this shape.
+    fn beaten_by(self) -> Self {
+        self.beats().beats()
+    }
+
+    fn score(self) -> u32 {
+        match self {
+            Self::Rock => 1,
+            Self::Paper => 2,
+            Self::Scissors => 3,
+        }
+    }
+
+    /// The outcome of playing this shape against `opponent`.
+    fn against(self, opponent: Self) -> Outcome {
+        if self == opponent {
+            Outcome::Draw
+        } else if self.beats() == opponent {
+            Outcome::Win
+        } else {
+            Outcome::Loss
+        }
+    }
 }
-impl FromStr for Part {
+
+impl FromStr for Shape {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
+            "A" => Self::Rock,
+            "B" => Self::Paper,
+            "C" => Self::Scissors,
+            other => bail!("{other:?} is not a shape, expected A, B or C"),
         })
     }
 }
-const INPUT: &str = include_str!("../input");
 
-fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    let output = match part {
-        Part::One => INPUT
-            .lines()
-            .map(|line| {
-                let (opponent, me) = line.split_once(' ').expect("Every line contains `<A> <X>`");
-                let opponent = opponent.as_bytes()[0] - b'A';
-                let me = me.as_bytes()[0] - b'X';
-                (me + 1
-                    + if opponent == me {
-                        3
-                    } else if (opponent + 1) % 3 == me {
-                        6
-                    } else {
-                        0
-                    }) as u32
-            })
-            .sum::<u32>(),
-
-        Part::Two => INPUT
-            .lines()
-            .map(|line| {
-                let (opponent, me) = line.split_once(' ').expect("Every line contains `<A> <X>`");
-                let opponent = opponent.as_bytes()[0] - b'A';
-                let me = me.as_bytes()[0] - b'X';
-                (me * 3 + 1 + (3 + opponent + me - 1) % 3) as u32
-            })
-            .sum::<u32>(),
-    };
-
-    println!("{output}");
-    Ok(())
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    fn score(self) -> u32 {
+        match self {
+            Self::Loss => 0,
+            Self::Draw => 3,
+            Self::Win => 6,
+        }
+    }
+
+    /// The shape to play against `opponent` to reach this outcome.
+    fn response_to(self, opponent: Shape) -> Shape {
+        match self {
+            Self::Loss => opponent.beats(),
+            Self::Draw => opponent,
+            Self::Win => opponent.beaten_by(),
+        }
+    }
+}
+
+/// The second column of the strategy guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Column {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "X" => Self::X,
+            "Y" => Self::Y,
+            "Z" => Self::Z,
+            other => bail!("{other:?} is not a response, expected X, Y or Z"),
+        })
+    }
+}
+
+/// An interpretation of what the second column of the strategy guide means.
+trait Strategy {
+    /// The shape to play against `opponent` when the guide says `column`.
+    fn respond(column: Column, opponent: Shape) -> Shape;
+}
+
+/// The second column is the shape to play.
+struct ColumnIsShape;
+
+impl Strategy for ColumnIsShape {
+    fn respond(column: Column, _: Shape) -> Shape {
+        match column {
+            Column::X => Shape::Rock,
+            Column::Y => Shape::Paper,
+            Column::Z => Shape::Scissors,
+        }
+    }
+}
+
+/// The second column is how the round needs to end.
+struct ColumnIsOutcome;
+
+impl Strategy for ColumnIsOutcome {
+    fn respond(column: Column, opponent: Shape) -> Shape {
+        match column {
+            Column::X => Outcome::Loss,
+            Column::Y => Outcome::Draw,
+            Column::Z => Outcome::Win,
+        }
+        .response_to(opponent)
+    }
+}
+
+fn score(opponent: Shape, me: Shape) -> u32 {
+    me.score() + me.against(opponent).score()
+}
+
+/// Parses each `<A> <X>` line of the strategy guide.
+fn rounds(input: &str) -> Result<Vec<(Shape, Column)>> {
+    Ok(parse::parse(input, |cursor| cursor.lines(round))?)
+}
+
+/// A word converted with [`FromStr`], failing at its start.
+fn symbol<T: FromStr<Err = Error>>(c: &mut Cursor) -> parse::Result<T> {
+    let start = *c;
+    c.word()?.parse().map_err(|error| start.error(error))
+}
+
+fn round(c: &mut Cursor) -> parse::Result<(Shape, Column)> {
+    let opponent = symbol(c)?;
+    c.tag(" ")?;
+    Ok((opponent, symbol(c)?))
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    check::parse_lines(input, round)
+}
+
+/// `size` random rounds.
+fn generate(rng: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let opponent = ["A", "B", "C"].choose(rng).unwrap();
+            let column = ["X", "Y", "Z"].choose(rng).unwrap();
+            format!("{opponent} {column}\n")
+        })
+        .collect()
+}
+
+fn shared<S: Strategy>(input: &str) -> Result<u32> {
+    Ok(rounds(input)?
+        .into_iter()
+        .map(|(opponent, column)| score(opponent, S::respond(column, opponent)))
+        .sum::<u32>())
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    shared::<ColumnIsShape>(input).map(Answer::from)
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    shared::<ColumnIsOutcome>(input).map(Answer::from)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+        A Y
+        B X
+        C Z
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "15";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "12";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn malformed() {
+        let error = super::rounds("A Y\nD X\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: \"D\" is not a shape, expected A, B or C"
+        );
+        let error = super::rounds("A Y\nAY\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:1: \"AY\" is not a shape, expected A, B or C"
+        );
+        let error = super::rounds("A Y\nB W\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "2:3: \"W\" is not a response, expected X, Y or Z"
+        );
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
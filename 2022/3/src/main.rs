@@ -1,65 +1,232 @@
-#![feature(iter_array_chunks)]
 #![doc = include_str!("../README.md")]
-use std::{env, str::FromStr};
+use std::str::FromStr;
 
-use anyhow::{bail, Error, Result};
+use anyhow::{bail, ensure, Context, Error, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng, SliceRandom},
+    runner::Runner,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+/// A set of items, stored as one bit per priority.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Items(u64);
+
+impl Items {
+    const ALL: Self = Self((1 << 52) - 1);
+
+    fn priority(item: char) -> Result<u32> {
+        Ok(match item {
+            'a'..='z' => item as u32 - 'a' as u32 + 1,
+            'A'..='Z' => item as u32 - 'A' as u32 + 27,
+            other => bail!("invalid item {other:?}"),
+        })
+    }
+
+    fn insert(&mut self, item: char) -> Result<()> {
+        self.0 |= 1 << (Self::priority(item)? - 1);
+        Ok(())
+    }
+
+    fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The items contained in all of `sets`.
+    fn common(sets: impl IntoIterator<Item = Self>) -> Self {
+        sets.into_iter().fold(Self::ALL, Self::intersection)
+    }
+
+    fn priorities(self) -> impl Iterator<Item = u32> {
+        (0..52)
+            .filter(move |bit| self.0 & 1 << bit != 0)
+            .map(|bit| bit + 1)
+    }
+
+    /// The priority of the only item in the set.
+    fn single(self) -> Result<u32> {
+        match self.0.count_ones() {
+            1 => Ok(self.0.trailing_zeros() + 1),
+            0 => bail!("there is no common item"),
+            count => bail!(
+                "there are {count} common items with priorities {:?}",
+                self.priorities().collect::<Vec<_>>()
+            ),
+        }
+    }
 }
-impl FromStr for Part {
+
+impl FromStr for Items {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
+        let mut items = Self::default();
+        for item in s.chars() {
+            items.insert(item)?;
+        }
+        Ok(items)
     }
 }
-const INPUT: &str = include_str!("../input");
 
-fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    let output = match part {
-        Part::One => INPUT
-            .lines()
-            .map(|line| {
-                let (a, b) = line.split_at(line.len() / 2);
-                let duplicate = a.chars().find(|a| b.contains(*a)).unwrap() as u8;
-                (if duplicate >= b'a' {
-                    duplicate - b'a'
-                } else {
-                    duplicate - b'A' + 26
-                } + 1) as u32
-            })
-            .sum::<u32>(),
-        Part::Two => INPUT
-            .lines()
-            .array_chunks::<3>()
-            .map(|[a, b, c]| {
-                let duplicate = a.chars().find(|&a| b.contains(a) && c.contains(a)).unwrap() as u8;
-                (if duplicate >= b'a' {
-                    duplicate - b'a'
-                } else {
-                    duplicate - b'A' + 26
-                } + 1) as u32
-            })
-            .sum(),
-    };
-
-    println!("{output}");
-    Ok(())
+/// The priority of the item in both compartments of `rucksack`.
+fn misplaced(rucksack: &str) -> Result<u32> {
+    ensure!(
+        rucksack.len().is_multiple_of(2),
+        "compartments differ in size"
+    );
+    let (a, b) = rucksack
+        .split_at_checked(rucksack.len() / 2)
+        .context("items are not ASCII letters")?;
+    Items::common([a.parse()?, b.parse()?]).single()
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check::lines(input, |line| misplaced(line).map(drop));
+    let rucksacks = input.lines().count();
+    if !rucksacks.is_multiple_of(3) {
+        problems.push(Problem::new(
+            None,
+            format_args!("{rucksacks} rucksacks cannot be split into groups of 3"),
+        ));
+    }
+    problems
+}
+
+/// `size` rucksacks, rounded up to whole groups of 3.
+///
+/// Apart from the badge, every item is only packed by one elf of a group, and
+/// the compartments of a rucksack only share the misplaced item.
+fn generate(rng: &mut Random, size: usize) -> String {
+    /// Fills a compartment of `len` items with `required` and items of `side`.
+    fn compartment(rng: &mut Random, len: usize, required: &[char], side: &[char]) -> Vec<char> {
+        let mut items = required.to_vec();
+        while items.len() < len {
+            items.push(*side.choose(rng).unwrap_or(&required[0]));
+        }
+        items.shuffle(rng);
+        items
+    }
+
+    let all: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut input = String::new();
+    for _ in 0..size.div_ceil(3) {
+        let mut items = all.clone();
+        items.shuffle(rng);
+        let badge = items.pop().unwrap();
+        let mut packed = vec![vec![badge]; 3];
+        for item in items {
+            packed[rng.gen_range(0..3)].push(item);
+        }
+        for mut items in packed {
+            items.shuffle(rng);
+            let misplaced = items.pop().unwrap();
+            let (a, b) = items.split_at(rng.gen_range(0..=items.len()));
+            let mut required = [vec![misplaced], vec![misplaced]];
+            if badge != misplaced {
+                let side = if a.contains(&badge) { 0 } else { 1 };
+                required[side].push(badge);
+            }
+            let len = rng.gen_range(2..=16);
+            input.extend(compartment(rng, len, &required[0], a));
+            input.extend(compartment(rng, len, &required[1], b));
+            input.push('\n');
+        }
+    }
+    input
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| misplaced(line).with_context(|| format!("line {}: {line:?}", idx + 1)))
+        .sum::<Result<u32>>()
+        .map(Answer::from)
+}
+
+/// Sums the priorities of the badges of each group of `size` elves.
+fn badges(input: &str, size: usize) -> Result<u32> {
+    ensure!(size > 0, "groups need at least one elf");
+    let rucksacks = input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            Items::from_str(line).with_context(|| format!("line {}: {line:?}", idx + 1))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        rucksacks.len().is_multiple_of(size),
+        "{} rucksacks cannot be split into groups of {size}",
+        rucksacks.len()
+    );
+    rucksacks
+        .chunks(size)
+        .enumerate()
+        .map(|(idx, group)| {
+            Items::common(group.iter().copied())
+                .single()
+                .with_context(|| format!("lines {}-{}", idx * size + 1, (idx + 1) * size))
+        })
+        .sum()
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    badges(input, 3).map(Answer::from)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+        vJrwpWtwJgWrhcsFMMfFFhFp
+        jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+        PmmdzqPrVvPwwTWBwg
+        wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+        ttgJtRGJQctTZtZT
+        CrZsJsPPZsGzwwsLwLmpwMDw
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "157";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "70";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(super::one("abcd\n").is_err());
+        assert!(super::one("ab»\n").is_err());
+        let error = super::one("abca\nab1b\n").err().unwrap();
+        assert_eq!(format!("{error:#}"), "line 2: \"ab1b\": invalid item '1'");
+        assert!(super::two("abc\nade\n").is_err());
+        assert!(super::two("abc\nade\nafg\nbcd\nefg\nhij\n").is_err());
+    }
+
+    #[test]
+    fn group_sizes() {
+        assert_eq!(super::badges("abc\ncde\nxyc\nbcA\ncd\n", 5).unwrap(), 3);
+        assert_eq!(super::badges("aZ\nZb\n", 2).unwrap(), 52);
+        assert_eq!(
+            super::badges("az\n", 1).unwrap_err().to_string(),
+            "lines 1-1"
+        );
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
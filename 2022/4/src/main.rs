@@ -1,59 +1,92 @@
 #![doc = include_str!("../README.md")]
-use std::{env, str::FromStr};
+use anyhow::Result;
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng},
+    interval::{self, Interval},
+    runner::Runner,
+};
 
-use anyhow::{bail, Error, Result};
-use range_ext::intersect::{Intersect, Intersection};
+const INPUT: &str = include_str!("../input");
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
 }
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    check::lines(input, |line| interval::parse_pair::<u32>(line).map(drop))
 }
-const INPUT: &str = include_str!("../input");
 
-fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    let input = INPUT.lines().map(|line| {
-        let (a, b) = line.split_once(',').unwrap();
-        let (a_f, a_t) = a.split_once('-').unwrap();
-        let (b_f, b_t) = b.split_once('-').unwrap();
-
-        let [a_f, a_t, b_f, b_t] = [a_f, a_t, b_f, b_t].map(|n| u32::from_str(n).unwrap());
-        (a_f..a_t + 1, b_f..b_t + 1)
-    });
-
-    let output = match part {
-        Part::One => input
-            .filter(|(a, b)| {
-                matches!(
-                    a.intersect(b),
-                    Intersection::Same | Intersection::Over | Intersection::Within
-                )
-            })
-            .count(),
-        Part::Two => input.filter(|(a, b)| a.intersect(b).is_any()).count(),
+/// `size` pairs of sections between 1 and 99.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let mut range = || {
+        let start = rng.gen_range(1..=99);
+        format!("{start}-{}", rng.gen_range(start..=99))
     };
+    (0..size)
+        .map(|_| format!("{},{}\n", range(), range()))
+        .collect()
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    Ok(interval::parse_pairs::<u32>(input)?
+        .into_iter()
+        .filter(|(a, b)| a.contains_range(b) || b.contains_range(a))
+        .count()
+        .into())
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    Ok(interval::parse_pairs::<u32>(input)?
+        .into_iter()
+        .filter(|(a, b)| a.overlaps(b))
+        .count()
+        .into())
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+        2-4,6-8
+        2-3,4-5
+        5-7,7-9
+        2-8,3-7
+        6-6,4-6
+        2-6,4-8
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "2";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "4";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn overflow() {
+        let input = "4294967295-4294967295,1-2\n";
+        assert!(super::one(input).is_err());
+        assert!(super::two(input).is_err());
+        assert_eq!(super::validate(input).len(), 1);
+    }
+
+    #[test]
+    fn reversed() {
+        let input = "5-3,1-9\n";
+        assert!(super::one(input).is_err());
+        assert!(super::two(input).is_err());
+        assert_eq!(super::validate(input).len(), 1);
+    }
 
-    println!("{output}");
-    Ok(())
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
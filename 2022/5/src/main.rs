@@ -1,102 +1,348 @@
-#![feature(iter_next_chunk, array_chunks, array_try_map)]
 #![doc = include_str!("../README.md")]
-use std::{env, mem, str::FromStr, vec};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
 
-use anyhow::{anyhow, bail, Context, Error, Result};
+use anyhow::{ensure, Context, Error, Result};
+use aoc::{
+    answer::Answer,
+    check::Problem,
+    compat,
+    generate::{Random, Rng},
+    parse,
+    runner::{Part, Runner},
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    let mut runner = Runner::from_env()?;
+
+    if runner.flag("--step") && !runner.check_input {
+        runner.finish()?;
+        let part = runner.part;
+        eprintln!("=== Solving Part {part:?} ===");
+        let crane = match part {
+            Part::One => Crane::CRATE_MOVER_9000,
+            Part::Two => Crane::CRATE_MOVER_9001,
+        };
+        let stacks = simulate(INPUT, crane, |instruction, stacks| {
+            eprintln!("{instruction}\n{stacks}");
+        })?;
+        println!("{}", stacks.tops()?);
+        return Ok(());
+    }
+
+    runner.generator(generate).run(INPUT, validate, one, two)
 }
-impl FromStr for Part {
+
+/// The stacks of crates, each stored bottom to top, in the order of their
+/// labels in the drawing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stacks {
+    labels: Vec<usize>,
+    stacks: Vec<Vec<char>>,
+}
+
+impl Stacks {
+    fn index(&self, label: usize) -> Result<usize> {
+        self.labels
+            .iter()
+            .position(|&l| l == label)
+            .with_context(|| format!("there is no stack labelled {label}"))
+    }
+
+    fn tops(&self) -> Result<String> {
+        self.labels
+            .iter()
+            .zip(&self.stacks)
+            .map(|(label, stack)| {
+                stack
+                    .last()
+                    .with_context(|| format!("stack {label} is empty"))
+            })
+            .collect()
+    }
+}
+
+impl Display for Stacks {
+    /// Draws the stacks the same way the puzzle input does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or_default();
+        for row in (0..height).rev() {
+            for (idx, stack) in self.stacks.iter().enumerate() {
+                if idx > 0 {
+                    f.write_str(" ")?;
+                }
+                match stack.get(row) {
+                    Some(krate) => write!(f, "[{krate}]")?,
+                    None => f.write_str("   ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+        for (idx, label) in self.labels.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{label:^3}")?;
+        }
+        writeln!(f)
+    }
+}
+
+impl FromStr for Stacks {
     type Err = Error;
 
+    /// Parses the drawing, i.e. the crate rows followed by the label line.
+    ///
+    /// Labels can be of any width, a crate belongs to the label it is drawn
+    /// above, and lines do not need to be padded to the full width.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
+        let mut lines = s.lines().rev();
+        let label_line = lines.next().context("drawing has a label line")?;
+        let mut columns = Vec::new();
+        let mut labels = Vec::new();
+        let mut rest = label_line;
+        while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+            let offset = label_line.len() - rest.len() + start;
+            let label = rest[start..].split_whitespace().next().unwrap_or_default();
+            labels.push(
+                usize::from_str(label).with_context(|| format!("invalid stack label {label:?}"))?,
+            );
+            columns.push(offset..offset + label.len());
+            rest = &rest[start + label.len()..];
+        }
+        ensure!(!labels.is_empty(), "label line has no labels");
+
+        let mut stacks = vec![Vec::new(); labels.len()];
+        for (row, line) in lines.enumerate() {
+            for (col, [open, krate, close]) in compat::windows(line.chars()).enumerate() {
+                if open != '[' || close != ']' {
+                    continue;
+                }
+                // Prefer the label right below the letter, but allow the
+                // label to be offset as long as it is below the brackets.
+                let stack = columns
+                    .iter()
+                    .position(|label| label.contains(&(col + 1)))
+                    .or_else(|| {
+                        columns
+                            .iter()
+                            .position(|label| label.start < col + 3 && col < label.end)
+                    })
+                    .with_context(|| {
+                        format!("crate [{krate}] in column {} is not above a label", col + 1)
+                    })?;
+                let stack = &mut stacks[stack];
+                ensure!(
+                    stack.len() == row,
+                    "crate [{krate}] in column {} is floating",
+                    col + 1
+                );
+                stack.push(krate);
+            }
+        }
+        Ok(Self { labels, stacks })
+    }
+}
+
+/// A single `move <count> from <from> to <to>` instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Move {
+    count: usize,
+    from: usize,
+    to: usize,
+}
+
+impl Move {
+    /// Parses the instruction on the `number`th line of the input.
+    fn parse(number: usize, line: &str) -> parse::Result<Self> {
+        parse::parse_line(number, line, |c| {
+            c.tag("move ")?;
+            let count = c.unsigned()?;
+            c.tag(" from ")?;
+            let from = c.unsigned()?;
+            c.tag(" to ")?;
+            Ok(Self {
+                count,
+                from,
+                to: c.unsigned()?,
+            })
         })
     }
 }
-const INPUT: &str = include_str!("../input");
 
-fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
-    }
-
-    Ok(())
-}
-
-fn shared(
-    input: &str,
-    op: fn(count: usize, from: &mut Vec<u8>, to: &mut Vec<u8>) -> (),
-) -> Result<String> {
-    let width = input.find('\n').context("there is at least one line")? + 1;
-    let height = (input.find('1').context("there is the label line")? - 1) / width;
-    let mut stacks = vec![Vec::with_capacity(height); width / 4];
-    for line in (0..height).rev() {
-        for (idx, krate) in input[line * width..(line + 1) * width]
-            .as_bytes()
-            .array_chunks::<4>()
-            .map(|&[_, c, ..]| c)
-            .enumerate()
-        {
-            if krate != b' ' {
-                stacks[idx].push(krate);
+impl Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { count, from, to } = self;
+        write!(f, "move {count} from {from} to {to}")
+    }
+}
+
+/// Parses the input into the starting stacks and the rearrangement procedure.
+fn parse(input: &str) -> Result<(Stacks, Vec<Move>)> {
+    let mut lines = input.lines().enumerate();
+    let drawing = lines
+        .by_ref()
+        .take_while(|(_, line)| !line.trim().is_empty())
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let stacks = drawing.parse().context("parsing drawing")?;
+    let moves = lines
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| Move::parse(idx + 1, line))
+        .collect::<parse::Result<_>>()?;
+    Ok((stacks, moves))
+}
+
+/// Reports unparsable lines and moves that cannot be executed.
+pub fn validate(input: &str) -> Vec<Problem> {
+    let drawing_lines = input
+        .lines()
+        .take_while(|line| !line.trim().is_empty())
+        .count();
+    let drawing = input
+        .lines()
+        .take(drawing_lines)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut stacks = match Stacks::from_str(&drawing) {
+        Ok(stacks) => Some(stacks),
+        Err(error) => {
+            let error = Problem::new(drawing_lines, format_args!("drawing: {error:#}"));
+            return vec![error];
+        }
+    };
+    let mut problems = Vec::new();
+    for (idx, line) in input.lines().enumerate().skip(drawing_lines + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let instruction = match Move::parse(idx + 1, line) {
+            Ok(instruction) => instruction,
+            Err(error) => {
+                problems.push(error.into());
+                continue;
+            }
+        };
+        // Crane models only differ in the order, not the number of crates.
+        if let Some(current) = &mut stacks {
+            if let Err(error) = Crane::CRATE_MOVER_9000.execute(current, instruction) {
+                problems.push(Problem::new(idx + 1, format_args!("{error:#}")));
+                stacks = None;
             }
         }
     }
-    for instruction in input[((height + 1) * width + 1)..].lines() {
-        let [_, count, _, from, _, to] = instruction
-            .split(' ')
-            .next_chunk()
-            .map_err(|_| anyhow!("wrong number of tokens in instruction {instruction:?}"))?;
-        let [count, from_idx, to_idx] = [count, from, to].try_map(usize::from_str)?;
-        let from_idx = from_idx - 1;
-        let to_idx = to_idx - 1;
-
-        let mut from = mem::take(&mut stacks[from_idx]);
-        let mut to = mem::take(&mut stacks[to_idx]);
-        op(count, &mut from, &mut to);
-        stacks[from_idx] = from;
-        stacks[to_idx] = to;
-    }
-    stacks
-        .iter_mut()
-        .map(|stack| {
-            stack
-                .last()
-                .context("empty stack")
-                .map(|&byte| byte as char)
-        })
-        .collect()
+    problems
 }
 
-fn one(input: &str) -> Result<String> {
-    shared(input, |count, from, to| {
-        to.extend(from.drain(from.len() - count..).rev());
-    })
+/// A crane that rearranges crates according to its move strategy.
+#[derive(Clone, Copy)]
+struct Crane {
+    /// Places the `lifted` crates (bottom to top as they were on the source
+    /// stack) onto the `target` stack.
+    strategy: fn(lifted: Vec<char>, target: &mut Vec<char>),
 }
 
-fn two(input: &str) -> Result<String> {
-    shared(input, |count, from, to| {
-        to.extend_from_slice(&from[from.len() - count..]);
-        from.truncate(from.len() - count);
-    })
+impl Crane {
+    /// Moves crates one at a time, reversing their order.
+    const CRATE_MOVER_9000: Self =
+        Self::new(|lifted, target| target.extend(lifted.into_iter().rev()));
+    /// Moves multiple crates at once, retaining their order.
+    const CRATE_MOVER_9001: Self = Self::new(|lifted, target| target.extend(lifted));
+
+    const fn new(strategy: fn(lifted: Vec<char>, target: &mut Vec<char>)) -> Self {
+        Self { strategy }
+    }
+
+    fn execute(&self, stacks: &mut Stacks, Move { count, from, to }: Move) -> Result<()> {
+        let from_idx = stacks.index(from)?;
+        let to_idx = stacks.index(to)?;
+        let source = &mut stacks.stacks[from_idx];
+        let remaining = source.len().checked_sub(count).with_context(|| {
+            format!(
+                "cannot move {count} crates from stack {from} holding {}",
+                source.len()
+            )
+        })?;
+        let lifted = source.split_off(remaining);
+        (self.strategy)(lifted, &mut stacks.stacks[to_idx]);
+        Ok(())
+    }
+}
+
+/// Runs the procedure, calling `on_step` with the stacks after each move.
+fn simulate(input: &str, crane: Crane, mut on_step: impl FnMut(Move, &Stacks)) -> Result<Stacks> {
+    let (mut stacks, moves) = parse(input)?;
+    for (idx, instruction) in moves.into_iter().enumerate() {
+        crane
+            .execute(&mut stacks, instruction)
+            .with_context(|| format!("executing instruction {}", idx + 1))?;
+        on_step(instruction, &stacks);
+    }
+    Ok(stacks)
+}
+
+/// Up to 9 stacks and about `size` moves, that leave no stack empty.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let count = rng.gen_range(3..=9);
+    let mut stacks = Stacks {
+        labels: (1..=count).collect(),
+        stacks: (0..count)
+            .map(|_| {
+                (0..rng.gen_range(1..=8))
+                    .map(|_| rng.gen_range('A'..='Z'))
+                    .collect()
+            })
+            .collect(),
+    };
+    let mut input = format!("{stacks}\n");
+    let mut execute = |stacks: &mut Stacks, count, from: usize, to: usize| {
+        let instruction = Move {
+            count,
+            from: from + 1,
+            to: to + 1,
+        };
+        Crane::CRATE_MOVER_9000
+            .execute(stacks, instruction)
+            .expect("move is valid");
+        input += &format!("{instruction}\n");
+    };
+    for _ in 0..size {
+        let from = loop {
+            let from = rng.gen_range(0..count);
+            if !stacks.stacks[from].is_empty() {
+                break from;
+            }
+        };
+        let to = (from + rng.gen_range(1..count)) % count;
+        let height = stacks.stacks[from].len();
+        execute(&mut stacks, rng.gen_range(1..=height), from, to);
+    }
+    // Every stack needs a crate on top. There are at least as many crates as
+    // stacks, so the tallest stack can spare one while any is empty.
+    while let Some(to) = stacks.stacks.iter().position(Vec::is_empty) {
+        let from = (0..count)
+            .max_by_key(|&idx| stacks.stacks[idx].len())
+            .unwrap();
+        execute(&mut stacks, 1, from, to);
+    }
+    input
+}
+
+fn shared(input: &str, crane: Crane) -> Result<String> {
+    simulate(input, crane, |_, _| {})?.tops()
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    shared(input, Crane::CRATE_MOVER_9000).map(Answer::from)
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    shared(input, Crane::CRATE_MOVER_9001).map(Answer::from)
 }
 
 #[cfg(test)]
@@ -118,12 +364,113 @@ mod test {
     #[test]
     fn one() {
         let output = "CMZ";
-        assert_eq!(super::one(INPUT).unwrap(), output);
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
     }
 
     #[test]
     fn two() {
         let output = "MCD";
-        assert_eq!(super::two(INPUT).unwrap(), output);
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    #[test]
+    fn ragged_and_wide() {
+        let input = [
+            "                                        [X]",
+            "[A] [B] [C] [D] [E] [F] [G] [H] [I] [J] [K]",
+            " 1   2   3   4   5   6   7   8   9  10  11",
+            "",
+            "move 2 from 11 to 1",
+            "move 1 from 1 to 11",
+        ]
+        .join("\n");
+        assert_eq!(super::one(&input).unwrap().to_string(), "XBCDEFGHIJK");
+        assert_eq!(super::two(&input).unwrap().to_string(), "KBCDEFGHIJX");
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let too_many = INPUT.replace("move 1 from 1 to 2", "move 5 from 1 to 2");
+        assert!(super::one(&too_many).is_err());
+        let missing = INPUT.replace("move 1 from 1 to 2", "move 1 from 1 to 4");
+        assert!(super::one(&missing).is_err());
+    }
+
+    #[test]
+    fn malformed_move() {
+        let input = INPUT.replace("move 3 from 1 to 3", "move 3 fro 1 to 3");
+        let error = super::one(&input).unwrap_err();
+        assert_eq!(error.to_string(), "7:7: expected \" from \", found ' '");
+        let problem = super::validate(&input).remove(0);
+        assert_eq!(
+            problem.to_string(),
+            "line 7: column 7: expected \" from \", found ' '"
+        );
+    }
+
+    #[test]
+    fn custom_crane() {
+        let crane = super::Crane::new(|_, _| {});
+        assert!(super::shared(INPUT, crane).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let drawing = INPUT.split("\n\n").next().unwrap();
+        let stacks: super::Stacks = drawing.parse().unwrap();
+        assert_eq!(stacks.to_string(), format!("{drawing}\n"));
+
+        let drawing = super::INPUT.split("\n\n").next().unwrap();
+        let stacks: super::Stacks = drawing.parse().unwrap();
+        assert_eq!(stacks.to_string(), format!("{drawing}\n"));
+    }
+
+    #[test]
+    fn steps() {
+        let mut diagrams = Vec::new();
+        super::simulate(INPUT, super::Crane::CRATE_MOVER_9000, |_, stacks| {
+            diagrams.push(stacks.to_string())
+        })
+        .unwrap();
+        assert_eq!(
+            diagrams[0],
+            indoc! {"
+                [D]        
+                [N] [C]    
+                [Z] [M] [P]
+                 1   2   3 
+            "}
+        );
+        assert_eq!(
+            diagrams[3],
+            indoc! {"
+                        [Z]
+                        [N]
+                        [D]
+                [C] [M] [P]
+                 1   2   3 
+            "}
+        );
+    }
+
+    #[test]
+    fn validate() {
+        assert!(super::validate(INPUT).is_empty());
+        let input = INPUT
+            .replace("move 3 from 1 to 3", "move 3 from 1 to 4")
+            .replace("move 1 from 1 to 2", "move one from 1 to 2");
+        let problems: Vec<_> = super::validate(&input)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                "line 7: there is no stack labelled 4",
+                "line 9: column 6: expected number, found 'o'"
+            ]
+        );
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
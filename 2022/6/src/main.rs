@@ -1,72 +1,177 @@
-#![feature(array_windows, slice_partition_dedup)]
 #![doc = include_str!("../README.md")]
-use std::{env, fmt::Display, str::FromStr};
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufReader, Bytes, Read},
+};
 
-use anyhow::{bail, Context, Error, Result};
-use itertools::Itertools;
+use anyhow::{bail, ensure, Context, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng, SliceRandom},
+    runner::{Part, Runner},
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
-}
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
-}
 const INPUT: &str = include_str!("../input");
 
 fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
+    let mut runner = Runner::from_env()?;
+    let all = runner.flag("--all");
+    let window = runner.value::<usize>("--window")?;
+    let path = runner.value::<String>("--input")?;
 
+    if runner.check_input || (!all && window.is_none() && path.is_none()) {
+        return runner.generator(generate).run(INPUT, validate, one, two);
+    }
+    runner.finish()?;
+
+    let part = runner.part;
     eprintln!("=== Solving Part {part:?} ===");
 
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
+    let window = window.unwrap_or(match part {
+        Part::One => 4,
+        Part::Two => 14,
+    });
+    let reader: Box<dyn Read> = match path.as_deref() {
+        None => Box::new(INPUT.as_bytes()),
+        Some("-") => Box::new(io::stdin().lock()),
+        Some(path) => Box::new(File::open(path).with_context(|| format!("opening {path}"))?),
+    };
+    let mut markers = Markers::new(reader, window)?;
+    if all {
+        for marker in markers {
+            println!("{}", marker?);
+        }
+    } else {
+        println!(
+            "{}",
+            markers.next().context("There should be a signal start")??
+        );
     }
 
     Ok(())
 }
 
-fn shared<const N: usize>(input: &str) -> Result<impl Display> {
-    let position = input
-        .as_bytes()
-        .array_windows::<N>()
-        .find_position(|&&window| {
-            let mut window = window;
-            window.sort_unstable();
-            window.partition_dedup().1.is_empty()
+/// Reports characters other than lowercase letters and additional lines.
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = check::lines(input.lines().next().unwrap_or_default(), |line| {
+        if let Some((column, c)) = line
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii_lowercase())
+        {
+            bail!("column {}: unexpected character {c:?}", column + 1);
+        }
+        Ok(())
+    });
+    if input.lines().count() > 1 {
+        problems.push(Problem::new(2, "the signal is followed by more lines"));
+    }
+    if input.trim().is_empty() {
+        problems.push(Problem::new(None, "the signal is empty"));
+    }
+    problems
+}
+
+/// A signal of about `size` characters, with the start of message marker
+/// after a noisy stretch using only a few distinct characters.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let mut alphabet: Vec<char> = ('a'..='z').collect();
+    alphabet.shuffle(rng);
+    let noise = &alphabet[..rng.gen_range(2..=6)];
+    let mut signal: String = (0..rng.gen_range(0..=size))
+        .map(|_| noise.choose(rng).unwrap())
+        .collect();
+    alphabet.shuffle(rng);
+    signal.extend(&alphabet[..14]);
+    while signal.len() < size {
+        signal.push(rng.gen_range('a'..='z'));
+    }
+    signal + "\n"
+}
+
+/// Streams the end positions of all windows of `size` distinct bytes in a
+/// signal.
+///
+/// Keeps a count per byte value of the current window, so every byte of the
+/// signal is only looked at twice, independently of the window size. The
+/// signal ends at the first line break or at the end of the reader.
+struct Markers<R> {
+    bytes: Bytes<BufReader<R>>,
+    size: usize,
+    window: VecDeque<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+    position: usize,
+}
+
+impl<R: Read> Markers<R> {
+    fn new(reader: R, size: usize) -> Result<Self> {
+        ensure!(size > 0, "window size must be positive");
+        Ok(Self {
+            bytes: BufReader::new(reader).bytes(),
+            size,
+            window: VecDeque::with_capacity(size + 1),
+            counts: [0; 256],
+            distinct: 0,
+            position: 0,
         })
-        .context("There should be a signal start")?
-        .0;
-    Ok(position + N)
+    }
 }
 
-fn one(input: &str) -> Result<impl Display> {
-    shared::<4>(input)
+impl<R: Read> Iterator for Markers<R> {
+    type Item = io::Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let byte = match self.bytes.next()? {
+                Ok(b'\n' | b'\r') => return None,
+                Ok(byte) => byte,
+                Err(err) => return Some(Err(err)),
+            };
+            self.position += 1;
+
+            self.window.push_back(byte);
+            self.counts[byte as usize] += 1;
+            if self.counts[byte as usize] == 1 {
+                self.distinct += 1;
+            }
+            if self.window.len() > self.size {
+                let byte = self.window.pop_front().expect("window is not empty");
+                self.counts[byte as usize] -= 1;
+                if self.counts[byte as usize] == 0 {
+                    self.distinct -= 1;
+                }
+            }
+
+            if self.distinct == self.size {
+                return Some(Ok(self.position));
+            }
+        }
+    }
+}
+
+fn shared(input: &str, size: usize) -> Result<usize> {
+    Ok(Markers::new(input.as_bytes(), size)?
+        .next()
+        .context("There should be a signal start")??)
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    shared(input, 4).map(Answer::from)
 }
 
-fn two(input: &str) -> Result<impl Display> {
-    shared::<14>(input)
+pub fn two(input: &str) -> Result<Answer> {
+    shared(input, 14).map(Answer::from)
 }
 
 #[cfg(test)]
 mod test {
+    use std::io;
+
     use indoc::indoc;
+    use proptest::{prop_assert_eq, proptest};
 
     const INPUT: &str = indoc! {r#"
         mjqjpqmgbljsphdztnvjfqwrcgsmlb
@@ -83,4 +188,44 @@ mod test {
         let output = "19";
         assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    #[test]
+    fn all_markers() {
+        let markers = super::Markers::new(INPUT.as_bytes(), 4)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(markers[..3], [7, 8, 9]);
+        assert_eq!(markers.last(), Some(&30));
+        assert!(super::Markers::new("aaaa".as_bytes(), 2)
+            .unwrap()
+            .next()
+            .is_none());
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
+
+    /// Sorts every window to check whether its characters are distinct.
+    fn reference(signal: &str, size: usize) -> Vec<usize> {
+        let signal = signal.lines().next().unwrap_or_default().as_bytes();
+        (size..=signal.len())
+            .filter(|&end| {
+                let mut window = signal[end - size..end].to_vec();
+                window.sort_unstable();
+                window.dedup();
+                window.len() == size
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn counted_windows(signal in "[a-f]{0,40}(\n[a-f]{0,10})?", size in 1usize..=8) {
+            let markers = super::Markers::new(signal.as_bytes(), size)
+                .unwrap()
+                .collect::<io::Result<Vec<_>>>()
+                .unwrap();
+            prop_assert_eq!(markers, reference(&signal, size));
+        }
+    }
 }
@@ -1,140 +1,243 @@
 #![doc = include_str!("../README.md")]
-use std::{env, fmt::Display, iter, mem, str::FromStr};
+use std::{iter, mem};
 
-use anyhow::{anyhow, bail, ensure, Context, Error, Result};
+use anyhow::{anyhow, ensure, Context, Error, Result};
+use aoc::{
+    answer::Answer,
+    check::Problem,
+    compat, debug,
+    generate::{word, Random, Rng, SliceRandom},
+    parse::{self, Cursor},
+    runner::Runner,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
-}
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
-}
 const INPUT: &str = include_str!("../input");
 
 fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
-    }
-
-    Ok(())
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
 }
 
 #[derive(Debug)]
-enum Line {
-    Cd(&'static str),
+enum Line<'a> {
+    Cd(&'a str),
     Ls,
-    Dir(&'static str),
+    Dir,
     File(u32),
     EoF,
 }
 
-impl TryFrom<&'static str> for Line {
-    type Error = Error;
-
-    fn try_from(value: &'static str) -> Result<Self, Self::Error> {
-        Ok(if let Some(command) = value.strip_prefix("$ ") {
-            if command == "ls" {
-                Self::Ls
-            } else if let Some(dir) = command.strip_prefix("cd ") {
-                Self::Cd(dir)
-            } else {
-                bail!("unknown command {command}")
+impl<'a> Line<'a> {
+    /// A command, or an entry listed by `ls`.
+    fn parse(c: &mut Cursor<'a>) -> parse::Result<Self> {
+        if c.eat("$ ") {
+            let start = *c;
+            return match c.word()? {
+                "ls" => Ok(Self::Ls),
+                "cd" => {
+                    c.tag(" ")?;
+                    Ok(Self::Cd(c.word()?))
+                }
+                other => Err(start.error(format_args!("unknown command {other:?}"))),
+            };
+        }
+        if c.eat("dir ") {
+            c.word()?;
+            return Ok(Self::Dir);
+        }
+        let size = c.unsigned()?;
+        c.tag(" ")?;
+        c.word()?;
+        Ok(Self::File(size))
+    }
+}
+
+/// Reports unparsable lines and `cd ..` leaving the root directory.
+pub fn validate(input: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if input.lines().next() != Some("$ cd /") {
+        problems.push(Problem::new(1, "first command is not `$ cd /`"));
+    }
+    let mut depth = 0usize;
+    for (idx, line) in input.lines().enumerate() {
+        match parse::parse_line(idx + 1, line, Line::parse) {
+            Ok(Line::Cd("/")) => depth = 0,
+            Ok(Line::Cd("..")) => match depth.checked_sub(1) {
+                Some(parent) => depth = parent,
+                None => problems.push(Problem::new(idx + 1, "trying to go past /")),
+            },
+            Ok(Line::Cd(_)) => depth += 1,
+            Ok(_) => {}
+            Err(error) => problems.push(error.into()),
+        }
+    }
+    problems
+}
+
+/// A transcript exploring `size` directories, but at least one, using
+/// between 40 and 70 million of the disk's 70 million.
+fn generate(rng: &mut Random, size: usize) -> String {
+    struct Dir {
+        name: String,
+        dirs: Vec<usize>,
+        files: Vec<(String, u32)>,
+    }
+
+    fn explore(dirs: &[Dir], dir: usize, rng: &mut Random, transcript: &mut Vec<String>) {
+        let dir = &dirs[dir];
+        transcript.push("$ ls".into());
+        let mut entries: Vec<_> = dir
+            .dirs
+            .iter()
+            .map(|&sub| format!("dir {}", dirs[sub].name))
+            .chain(
+                dir.files
+                    .iter()
+                    .map(|(name, size)| format!("{size} {name}")),
+            )
+            .collect();
+        entries.shuffle(rng);
+        transcript.extend(entries);
+        for &sub in &dir.dirs {
+            transcript.push(format!("$ cd {}", dirs[sub].name));
+            explore(dirs, sub, rng, transcript);
+            transcript.push("$ cd ..".into());
+        }
+    }
+
+    let mut dirs = vec![Dir {
+        name: "/".into(),
+        dirs: Vec::new(),
+        files: Vec::new(),
+    }];
+    for idx in 1..size.max(1) {
+        let parent = rng.gen_range(0..idx);
+        let name = loop {
+            let name = word(rng, 1..9);
+            if dirs[parent].dirs.iter().all(|&sub| dirs[sub].name != name) {
+                break name;
             }
-        } else if let Some(dir) = value.strip_prefix("dir ") {
-            Self::Dir(dir)
-        } else {
-            let (size, _name) = value.split_once(' ').context("parsing file entry")?;
-            Self::File(size.parse().context("parsing file size")?)
-        })
+        };
+        dirs[parent].dirs.push(idx);
+        dirs.push(Dir {
+            name,
+            dirs: Vec::new(),
+            files: Vec::new(),
+        });
     }
+
+    // Half the files are small, together at most a quarter of the total, the
+    // rest share what is left.
+    let files = rng.gen_range(2..=4) * dirs.len();
+    let total = rng.gen_range(40_000_000..70_000_000);
+    let max_small = (total / 4 / (files / 2).max(1) as u32).clamp(2, 20_000);
+    let small: Vec<u32> = (0..files / 2)
+        .map(|_| rng.gen_range(1..max_small))
+        .collect();
+    let rest = total - small.iter().sum::<u32>();
+    let mut cuts: Vec<u32> = (0..files - small.len() - 1)
+        .map(|_| rng.gen_range(1..rest))
+        .collect();
+    cuts.extend([0, rest]);
+    cuts.sort_unstable();
+    let sizes = small
+        .iter()
+        .copied()
+        .chain(compat::windows(cuts).map(|[start, end]| end - start))
+        .filter(|&size| size > 0);
+    for size in sizes {
+        let dir = rng.gen_range(0..dirs.len());
+        let mut name = word(rng, 1..9);
+        if rng.gen_bool(0.5) {
+            name += &format!(".{}", word(rng, 1..4));
+        }
+        dirs[dir].files.push((name, size));
+    }
+
+    let mut transcript = vec!["$ cd /".to_string()];
+    explore(&dirs, 0, rng, &mut transcript);
+    // Real transcripts do not return to the root at the end.
+    while transcript.last().is_some_and(|line| line == "$ cd ..") {
+        transcript.pop();
+    }
+    transcript.join("\n") + "\n"
 }
 
-fn shared(input: &'static str) -> Result<impl Iterator<Item = Result<u32>>> {
+fn shared(input: &str) -> Result<impl Iterator<Item = Result<u64>> + '_> {
     let mut input = input.lines();
     ensure!(input.next() == Some("$ cd /"), "first command is cd /");
-    let mut stack = Vec::<(&str, u32)>::new();
+    let mut stack = Vec::<(&str, u64)>::new();
     let mut current_dir = "/";
     let mut current_sum = 0;
-    Ok(input.map(Line::try_from).chain(iter::once(Ok(Line::EoF))).flat_map(move |line| -> Box<dyn Iterator<Item = Result<u32, Error>>>{
-        if let Ok(line) = line {
-            match line {
-                Line::Cd("..") => {
-                    let old_sum = current_sum;
+    Ok(input
+        .enumerate()
+        .map(|(idx, line)| parse::parse_line(idx + 2, line, Line::parse).map_err(Error::from))
+        .chain(iter::once(Ok(Line::EoF)))
+        .flat_map(
+            move |line| -> Box<dyn Iterator<Item = Result<u64, Error>>> {
+                if let Ok(line) = line {
+                    match line {
+                        Line::Cd("..") => {
+                            let old_sum = current_sum;
 
-                    let Some(parent) = stack.pop() else {return Box::new(iter::once(Err(anyhow!("Trying to go passt /"))))};
-                    current_dir = parent.0;
-                    current_sum += parent.1;
+                            let Some(parent) = stack.pop() else {
+                                return Box::new(iter::once(Err(anyhow!("Trying to go passt /"))));
+                            };
+                            current_dir = parent.0;
+                            current_sum += parent.1;
 
-                    return Box::new(iter::once(Ok(old_sum)))
-                }
-                Line::Cd(dir) => {
-                    stack.push((current_dir, current_sum));
-                    current_dir = dir;
-                    current_sum = 0;
-                }
-                Line::Ls | Line::Dir(_) => {},
-                Line::File(file_size) =>  current_sum += file_size,
-                Line::EoF => {
-                    let mut current_sum = current_sum;
-                    return Box::new(mem::take(&mut stack)
-                        .into_iter()
-                        .chain(iter::once(("/", 0)))
-                        .map(move |(_,size)| {
-                            let old_sum = current_sum;
-                            current_sum += size;
-                            anyhow::Ok(old_sum)
-                        }))
-                }
-            }
+                            return Box::new(iter::once(Ok(old_sum)));
+                        }
+                        Line::Cd(dir) => {
+                            stack.push((current_dir, current_sum));
+                            current_dir = dir;
+                            current_sum = 0;
+                        }
+                        Line::Ls | Line::Dir => {}
+                        Line::File(file_size) => current_sum += u64::from(file_size),
+                        Line::EoF => {
+                            let mut current_sum = current_sum;
+                            return Box::new(
+                                mem::take(&mut stack)
+                                    .into_iter()
+                                    .chain(iter::once(("/", 0)))
+                                    .map(move |(_, size)| {
+                                        let old_sum = current_sum;
+                                        current_sum += size;
+                                        anyhow::Ok(old_sum)
+                                    }),
+                            );
+                        }
+                    }
                     Box::new(iter::empty())
-        } else {
-            Box::new(iter::once(Err(line.unwrap_err())))
-        }
-    }))
+                } else {
+                    Box::new(iter::once(Err(line.unwrap_err())))
+                }
+            },
+        ))
 }
 
-fn one(input: &'static str) -> Result<impl Display> {
+pub fn one(input: &str) -> Result<Answer> {
     shared(input)?
-        .filter(|size| {
-            if let &Ok(size) = size {
-                size < 100000
-            } else {
-                false
-            }
-        })
-        .sum::<Result<u32>>()
+        .filter(|size| size.as_ref().map_or(true, |&size| size < 100000))
+        .sum::<Result<u64>>()
+        .map(Answer::from)
 }
 
-fn two(input: &'static str) -> Result<impl Display> {
+pub fn two(input: &str) -> Result<Answer> {
     let input = shared(input)?.collect::<Result<Vec<_>>>()?;
-    let min_size = 30_000_000 - (70_000_000 - input.last().context("empty folder size array")?);
-    dbg!(min_size);
+    let used = *input.last().context("empty folder size array")?;
+    let min_size = used
+        .checked_sub(70_000_000 - 30_000_000)
+        .context("there is enough free space already")?;
+    debug!("{min_size} bytes have to be freed");
     input
         .into_iter()
         .filter(|&dir| dir > min_size)
         .min()
         .context("no large enough folder")
+        .map(Answer::from)
 }
 
 #[cfg(test)]
@@ -178,4 +281,38 @@ mod test {
         let output = "24933642";
         assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    #[test]
+    fn malformed() {
+        let error = super::two("$ cd /\n$ ls\n100 a\n").err().unwrap();
+        assert_eq!(error.to_string(), "there is enough free space already");
+        let error = super::one("$ cd /\n$ ls\n100 a\n$ mv a b\n").unwrap_err();
+        assert_eq!(error.to_string(), "4:3: unknown command \"mv\"");
+        let error = super::one("$ cd /\n$ ls\nx a\n").unwrap_err();
+        assert_eq!(error.to_string(), "3:1: expected number, found 'x'");
+        let problems = super::validate("$ cd /\n$ cd ..\n$ cd\n");
+        let problems: Vec<_> = problems.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            problems,
+            [
+                "line 2: trying to go past /",
+                "line 3: column 5: expected \" \", found end of input"
+            ]
+        );
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
+
+    #[test]
+    fn generated_large() {
+        let stress = aoc::generate::stress(
+            super::generate,
+            super::validate,
+            super::one,
+            super::two,
+            0..3,
+            8000,
+        );
+        stress.check().unwrap();
+    }
 }
@@ -1,44 +1,21 @@
-#![feature(is_some_and)]
 #![doc = include_str!("../README.md")]
-use std::{env, fmt::Display, ops::ControlFlow, str::FromStr};
+use std::ops::ControlFlow;
 
-use anyhow::{bail, Context, Error, Result};
+use anyhow::{bail, ensure, Context, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng},
+    profile,
+    runner::Runner,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
-}
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
-}
 const INPUT: &str = include_str!("../input");
 
 fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
-
-    eprintln!("=== Solving Part {part:?} ===");
-
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
-    }
-
-    Ok(())
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
 }
 
 trait Value<T> {
@@ -65,13 +42,15 @@ fn find_first_idx<T: PartialOrd>(
     range: impl Iterator<Item = T>,
     direction: Direction,
 ) -> Vec<Vec<Option<usize>>> {
-    let width = match direction {
-        Direction::Top | Direction::Bottom => grid[0].len(),
-        Direction::Left | Direction::Right => grid.len(),
+    // Top and bottom scan the columns row by row, left and right the rows
+    // column by column.
+    let (width, depth) = match direction {
+        Direction::Top | Direction::Bottom => (grid[0].len(), grid.len()),
+        Direction::Left | Direction::Right => (grid.len(), grid[0].len()),
     };
     range
         .map(move |height| {
-            (0..grid.len())
+            (0..depth)
                 .try_fold(vec![None; width], |mut aggr, row| {
                     let mut filled = 0;
                     for col in 0..width {
@@ -98,20 +77,82 @@ fn find_first_idx<T: PartialOrd>(
         .collect()
 }
 
-fn one(input: &str) -> Result<impl Display> {
-    let input = input
+/// Reports characters other than digits and rows of differing width.
+pub fn validate(input: &str) -> Vec<Problem> {
+    let width = input.lines().next().map_or(0, str::len);
+    let mut problems = check::lines(input, |line| {
+        if let Some((column, c)) = line.chars().enumerate().find(|(_, c)| !c.is_ascii_digit()) {
+            bail!("column {}: {c:?} is not a tree height", column + 1);
+        }
+        ensure!(
+            line.len() == width,
+            "row has {} trees, expected {width}",
+            line.len()
+        );
+        Ok(())
+    });
+    if width == 0 {
+        problems.push(Problem::new(None, "there are no trees"));
+    }
+    problems
+}
+
+/// A forest of `size` by `size` trees, but at least 3 by 3.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let size = size.max(3);
+    (0..size)
+        .map(|_| {
+            let mut row: String = (0..size).map(|_| rng.gen_range('0'..='9')).collect();
+            row.push('\n');
+            row
+        })
+        .collect()
+}
+
+/// The tree heights, row by row.
+fn forest(input: &str) -> Result<Vec<Vec<u8>>> {
+    let width = input.lines().next().map_or(0, str::len);
+    ensure!(width > 0, "there are no trees");
+    input
         .lines()
-        .map(|line| line.as_bytes().iter().map(|c| c - b'0').collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+        .enumerate()
+        .map(|(idx, line)| {
+            ensure!(
+                line.len() == width,
+                "line {}: row has {} trees, expected {width}",
+                idx + 1,
+                line.len()
+            );
+            line.chars()
+                .map(|c| {
+                    c.to_digit(10)
+                        .map(|height| height as u8)
+                        .with_context(|| format!("line {}: {c:?} is not a tree height", idx + 1))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    let input = profile::time("parse", || forest(input))?;
 
     let width = input[0].len() - 1;
     let height = input.len() - 1;
 
-    let top = &find_first_idx(&input, 0..=9, Direction::Top);
-    let left = &find_first_idx(&input, 0..=9, Direction::Left);
-    let right = &find_first_idx(&input, 0..=9, Direction::Right);
-    let bottom = &find_first_idx(&input, 0..=9, Direction::Bottom);
+    let tables = profile::span("tables");
+    let find = |direction| {
+        profile::time("find_first_idx", || {
+            find_first_idx(&input, 0..=9, direction)
+        })
+    };
+    let top = &find(Direction::Top);
+    let left = &find(Direction::Left);
+    let right = &find(Direction::Right);
+    let bottom = &find(Direction::Bottom);
+    drop(tables);
 
+    let _count = profile::span("count");
     Ok(input
         .into_iter()
         .enumerate()
@@ -123,14 +164,13 @@ fn one(input: &str) -> Result<impl Display> {
                     && bottom[value as usize][col].is_some_and(|r| r < (height - row)))
             })
         })
-        .count())
+        .count()
+        .into())
 }
 
-fn two(input: &str) -> Result<impl Display> {
-    let input = &input
-        .lines()
-        .map(|line| line.as_bytes().iter().map(|c| c - b'0').collect::<Vec<_>>())
-        .collect::<Vec<_>>();
+pub fn two(input: &str) -> Result<Answer> {
+    let input = &profile::time("parse", || forest(input))?;
+    let _search = profile::span("search");
     input[..input.len() - 1]
         .iter()
         .enumerate()
@@ -164,11 +204,13 @@ fn two(input: &str) -> Result<impl Display> {
         })
         .max()
         .context("there is one tree")
+        .map(Answer::from)
 }
 
 #[cfg(test)]
 mod test {
     use indoc::indoc;
+    use proptest::{collection::vec, prop_assert_eq, proptest, strategy::Strategy};
 
     const INPUT: &str = indoc! {r#"
         30373
@@ -189,4 +231,68 @@ mod test {
         let output = "8";
         assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    #[test]
+    fn malformed() {
+        assert!(super::one("").is_err());
+        assert!(super::two("123\n45\n678\n").is_err());
+        let error = super::one("123\n4x6\n").err().unwrap();
+        assert_eq!(error.to_string(), "line 2: 'x' is not a tree height");
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
+
+    /// The trees in each direction from `(row, col)`, nearest first.
+    fn lines_of_sight(grid: &[Vec<u8>], row: usize, col: usize) -> [Vec<u8>; 4] {
+        [
+            (0..row).rev().map(|r| grid[r][col]).collect(),
+            (row + 1..grid.len()).map(|r| grid[r][col]).collect(),
+            (0..col).rev().map(|c| grid[row][c]).collect(),
+            (col + 1..grid[0].len()).map(|c| grid[row][c]).collect(),
+        ]
+    }
+
+    /// Looks along every line of sight from every tree.
+    fn reference(grid: &[Vec<u8>]) -> (usize, usize) {
+        let mut visible = 0;
+        let mut best = 0;
+        for row in 0..grid.len() {
+            for col in 0..grid[0].len() {
+                let height = grid[row][col];
+                let lines = lines_of_sight(grid, row, col);
+                if lines
+                    .iter()
+                    .any(|line| line.iter().all(|&tree| tree < height))
+                {
+                    visible += 1;
+                }
+                let score = lines
+                    .iter()
+                    .map(|line| match line.iter().position(|&tree| tree >= height) {
+                        Some(idx) => idx + 1,
+                        None => line.len(),
+                    })
+                    .product();
+                best = best.max(score);
+            }
+        }
+        (visible, best)
+    }
+
+    fn grid() -> impl Strategy<Value = Vec<Vec<u8>>> {
+        (3usize..10, 3usize..10).prop_flat_map(|(width, height)| vec(vec(0u8..10, width), height))
+    }
+
+    proptest! {
+        #[test]
+        fn precomputed_tables(grid in grid()) {
+            let input: String = grid
+                .iter()
+                .map(|row| row.iter().map(|tree| tree.to_string()).collect::<String>() + "\n")
+                .collect();
+            let (visible, best) = reference(&grid);
+            prop_assert_eq!(super::one(&input).unwrap().to_string(), visible.to_string());
+            prop_assert_eq!(super::two(&input).unwrap().to_string(), best.to_string());
+        }
+    }
 }
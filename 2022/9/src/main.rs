@@ -1,60 +1,68 @@
 #![doc = include_str!("../README.md")]
-use std::{collections::HashSet, env, fmt::Display, str::FromStr};
+use std::collections::HashSet;
 
-use anyhow::{bail, Context, Error, Result};
+use anyhow::{ensure, Result};
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng, SliceRandom},
+    parse::{self, Cursor},
+    runner::Runner,
+};
 
-#[derive(Default, Debug)]
-enum Part {
-    #[default]
-    One,
-    Two,
-}
-impl FromStr for Part {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(match s {
-            "1" => Self::One,
-            "2" => Self::Two,
-            other => bail!("{other} is not a valid part"),
-        })
-    }
-}
 const INPUT: &str = include_str!("../input");
 
 fn main() -> Result<()> {
-    let part = env::args()
-        .nth(1)
-        .as_deref()
-        .map(Part::from_str)
-        .transpose()?
-        .unwrap_or_default();
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
 
-    eprintln!("=== Solving Part {part:?} ===");
+/// A `<D> <S>` line, as the direction to move in and the number of steps.
+fn motion(c: &mut Cursor) -> parse::Result<((i32, i32), u32)> {
+    let start = *c;
+    let direction = match c.word()? {
+        "U" => (0, 1),
+        "L" => (-1, 0),
+        "R" => (1, 0),
+        "D" => (0, -1),
+        other => return Err(start.error(format_args!("Unsupported direction {other}"))),
+    };
+    c.tag(" ")?;
+    Ok((direction, c.unsigned()?))
+}
 
-    match part {
-        Part::One => println!("{}", one(INPUT)?),
-        Part::Two => println!("{}", two(INPUT)?),
-    }
+pub fn validate(input: &str) -> Vec<Problem> {
+    check::parse_lines(input, motion)
+}
 
-    Ok(())
+/// `size` motions of up to 20 steps.
+fn generate(rng: &mut Random, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let direction = ["U", "L", "R", "D"].choose(rng).unwrap();
+            format!("{direction} {}\n", rng.gen_range(1..=20))
+        })
+        .collect()
 }
 
-fn shared<const N: usize>(input: &str) -> Result<impl Display> {
+fn shared<const N: usize>(input: &str) -> Result<usize> {
     let mut visited = HashSet::<(i32, i32)>::new();
     let mut rope = [(0i32, 0i32); N];
-    for instruction in input.lines() {
-        let (direction, steps) = instruction
-            .split_once(' ')
-            .context("instruction format is `<D> <S>`")?;
-        for _ in 0..steps.parse()? {
-            match direction {
-                "U" => rope[0].1 += 1,
-                "L" => rope[0].0 -= 1,
-                "R" => rope[0].0 += 1,
-                "D" => rope[0].1 -= 1,
-                other => bail!("Unsupported direction {other}"),
-            }
+    let instructions = parse::parse(input, |cursor| cursor.lines(motion))?;
+    let distance: u64 = instructions
+        .iter()
+        .map(|&(_, steps)| u64::from(steps))
+        .sum();
+    ensure!(
+        distance <= i32::MAX as u64,
+        "the rope moves further than {} steps",
+        i32::MAX
+    );
+    for ((dx, dy), steps) in instructions {
+        for _ in 0..steps {
+            rope[0].0 += dx;
+            rope[0].1 += dy;
             for idx in 1..N {
                 let h = rope[idx - 1];
                 let t = &mut rope[idx];
@@ -73,12 +81,12 @@ fn shared<const N: usize>(input: &str) -> Result<impl Display> {
     Ok(visited.len())
 }
 
-fn one(input: &str) -> Result<impl Display> {
-    shared::<2>(input)
+pub fn one(input: &str) -> Result<Answer> {
+    shared::<2>(input).map(Answer::from)
 }
 
-fn two(input: &str) -> Result<impl Display> {
-    shared::<10>(input)
+pub fn two(input: &str) -> Result<Answer> {
+    shared::<10>(input).map(Answer::from)
 }
 
 #[cfg(test)]
@@ -116,4 +124,6 @@ mod test {
         let output = "36";
         assert_eq!(super::two(INPUT).unwrap().to_string(), output);
     }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
 }
@@ -0,0 +1,129 @@
+//! The answer of a part, keeping whether it is a number, some text or a
+//! picture that still has to be read.
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Integer(i128),
+    /// A single line, like the crates on top of each stack.
+    Text(String),
+    /// Rows of ASCII art, like letters drawn on a screen.
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// The rows of `text`, ignoring a trailing newline.
+    pub fn grid(text: &str) -> Self {
+        Self::Grid(text.lines().map(String::from).collect())
+    }
+
+    /// What to submit, unless the answer has to be read by a human first.
+    pub fn submission(&self) -> Option<String> {
+        match self {
+            Self::Integer(value) => Some(value.to_string()),
+            Self::Text(text) if !text.is_empty() && !text.contains(char::is_whitespace) => {
+                Some(text.clone())
+            }
+            Self::Text(_) | Self::Grid(_) => None,
+        }
+    }
+
+    /// Whether the answer is the `recorded` one, or `None` if that cannot be
+    /// told, as with pictures of letters.
+    pub fn matches(&self, recorded: &str) -> Option<bool> {
+        let recorded = recorded.trim();
+        match self {
+            Self::Integer(value) => Some(recorded.parse() == Ok(*value)),
+            Self::Text(text) => Some(text == recorded),
+            Self::Grid(_) => None,
+        }
+    }
+}
+
+macro_rules! integer {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Answer {
+                fn from(value: $ty) -> Self {
+                    Self::Integer(value.into())
+                }
+            }
+        )*
+    };
+}
+
+integer!(u8, u16, u32, u64, i8, i16, i32, i64, i128);
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Self::Integer(value as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(value: isize) -> Self {
+        Self::Integer(value as i128)
+    }
+}
+
+impl From<String> for Answer {
+    /// Makes text with several lines a [`Grid`](Self::Grid).
+    fn from(text: String) -> Self {
+        if text.trim_end_matches('\n').contains('\n') {
+            Self::grid(&text)
+        } else {
+            Self::Text(text)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        text.to_string().into()
+    }
+}
+
+impl Display for Answer {
+    /// Writes every row of a grid followed by a newline, the rest as is.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Text(text) => f.write_str(text),
+            Self::Grid(rows) => rows.iter().try_for_each(|row| writeln!(f, "{row}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn convert() {
+        assert_eq!(Answer::from(17926061332u64), Answer::Integer(17926061332));
+        assert_eq!(Answer::from(-3i32), Answer::Integer(-3));
+        assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+        assert_eq!(Answer::from("CQQBBJFCS"), Answer::Text("CQQBBJFCS".into()));
+        let grid = Answer::from("#..#\n.##.\n".to_string());
+        assert_eq!(grid, Answer::Grid(vec!["#..#".into(), ".##.".into()]));
+        assert_eq!(grid.to_string(), "#..#\n.##.\n");
+    }
+
+    #[test]
+    fn submit() {
+        assert_eq!(Answer::from(42u8).submission().as_deref(), Some("42"));
+        assert_eq!(
+            Answer::from("RFFFWBPNS").submission().as_deref(),
+            Some("RFFFWBPNS")
+        );
+        assert_eq!(Answer::from("").submission(), None);
+        assert_eq!(Answer::from("two words").submission(), None);
+        assert_eq!(Answer::grid("#.\n.#\n").submission(), None);
+
+        assert_eq!(Answer::from(72718u32).matches("72718"), Some(true));
+        assert_eq!(Answer::from(72718u32).matches("072718"), Some(true));
+        assert_eq!(Answer::from(1u32).matches("x"), Some(false));
+        assert_eq!(Answer::from("ABC").matches("ABD"), Some(false));
+        assert_eq!(Answer::grid("#.\n").matches("EHPZPJGL"), None);
+    }
+}
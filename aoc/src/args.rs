@@ -0,0 +1,87 @@
+//! Command line arguments, consumed one flag at a time.
+use std::{env, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    pub fn new(args: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            args: args.into_iter().collect(),
+        }
+    }
+
+    /// The arguments the program was started with.
+    pub fn from_env() -> Self {
+        Self::new(env::args().skip(1))
+    }
+
+    /// The first argument if it is not a flag, consuming it.
+    pub fn positional(&mut self) -> Option<String> {
+        let first = self.args.first()?;
+        (!first.starts_with('-')).then(|| self.args.remove(0))
+    }
+
+    /// Whether `name` was passed, consuming it.
+    pub fn flag(&mut self, name: &str) -> bool {
+        let len = self.args.len();
+        self.args.retain(|arg| arg != name);
+        self.args.len() != len
+    }
+
+    /// The value following `name`, consuming both.
+    pub fn value<T>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        let Some(idx) = self.args.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        let value = self
+            .args
+            .get(idx + 1)
+            .with_context(|| format!("{name} expects a value"))?
+            .parse()
+            .with_context(|| format!("invalid value for {name}"))?;
+        self.args.drain(idx..idx + 2);
+        Ok(Some(value))
+    }
+
+    /// Fails if any argument was not consumed.
+    pub fn finish(&self) -> Result<()> {
+        match self.args.first() {
+            Some(arg) => bail!("unknown argument {arg:?}"),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn consume() {
+        let mut args = Args::new(["new", "2022", "--kind", "grid", "--wait"].map(String::from));
+        assert_eq!(args.positional().as_deref(), Some("new"));
+        assert_eq!(
+            args.value::<String>("--kind").unwrap().as_deref(),
+            Some("grid")
+        );
+        assert!(args.flag("--wait"));
+        assert!(!args.flag("--wait"));
+        assert!(args.finish().is_err());
+        assert_eq!(args.positional().as_deref(), Some("2022"));
+        assert_eq!(args.positional(), None);
+        args.finish().unwrap();
+
+        let mut args = Args::new(["--seed".to_string()]);
+        assert_eq!(args.positional(), None);
+        assert!(args.value::<u64>("--seed").is_err());
+    }
+}
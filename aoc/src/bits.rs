@@ -0,0 +1,178 @@
+//! Column statistics over lists of fixed width binary numbers.
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Context, Error, Result};
+
+/// How often a column holds a `0` and a `1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnStats {
+    pub zeros: usize,
+    pub ones: usize,
+}
+
+impl ColumnStats {
+    /// The more common bit, `1` on ties.
+    pub fn most_common(self) -> bool {
+        self.ones >= self.zeros
+    }
+
+    /// The less common bit, `0` on ties.
+    pub fn least_common(self) -> bool {
+        !self.most_common()
+    }
+}
+
+/// Binary numbers of the same width, each packed into a `u64`.
+///
+/// Columns are counted from the left, i.e. column `0` is the most significant
+/// bit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitReport {
+    width: usize,
+    numbers: Vec<u64>,
+}
+
+impl BitReport {
+    pub fn new(width: usize, numbers: Vec<u64>) -> Result<Self> {
+        ensure!(
+            (1..=64).contains(&width),
+            "width must be between 1 and 64, not {width}"
+        );
+        ensure!(
+            numbers.iter().all(|&number| number >> (width - 1) <= 1),
+            "numbers must fit into {width} bits"
+        );
+        Ok(Self { width, numbers })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn numbers(&self) -> &[u64] {
+        &self.numbers
+    }
+
+    fn mask(&self, column: usize) -> u64 {
+        1 << (self.width - 1 - column)
+    }
+
+    pub fn column(&self, column: usize) -> ColumnStats {
+        Self::stats(&self.numbers, self.mask(column))
+    }
+
+    fn stats(numbers: &[u64], mask: u64) -> ColumnStats {
+        let ones = numbers.iter().filter(|&&number| number & mask != 0).count();
+        ColumnStats {
+            zeros: numbers.len() - ones,
+            ones,
+        }
+    }
+
+    /// Builds a number from the bit `criterion` picks for each column.
+    pub fn combine(&self, criterion: impl Fn(ColumnStats) -> bool) -> u64 {
+        (0..self.width).fold(0, |aggr, column| {
+            aggr << 1 | criterion(self.column(column)) as u64
+        })
+    }
+
+    /// The number made of the most common bit of each column.
+    pub fn gamma(&self) -> u64 {
+        self.combine(ColumnStats::most_common)
+    }
+
+    /// The number made of the least common bit of each column.
+    pub fn epsilon(&self) -> u64 {
+        self.combine(ColumnStats::least_common)
+    }
+
+    /// Going through the columns from the left, keeps only the numbers whose
+    /// bit matches the one `criterion` picks for the remaining numbers, until
+    /// a single number is left.
+    pub fn filter_by(&self, criterion: impl Fn(ColumnStats) -> bool) -> Result<u64> {
+        let mut numbers = self.numbers.clone();
+        for column in 0..self.width {
+            if numbers.len() <= 1 {
+                break;
+            }
+            let mask = self.mask(column);
+            let bit = criterion(Self::stats(&numbers, mask));
+            numbers.retain(|&number| (number & mask != 0) == bit);
+        }
+        match numbers[..] {
+            [number] => Ok(number),
+            [] => bail!("no number matches the criterion"),
+            _ => bail!("{} numbers match the criterion", numbers.len()),
+        }
+    }
+}
+
+impl BitReport {
+    /// Parses a single binary number of `width` bits.
+    pub fn parse_number(s: &str, width: usize) -> Result<u64> {
+        ensure!(s.len() == width, "expected {width} bits, found {}", s.len());
+        s.chars().try_fold(0, |aggr, c| {
+            Ok(aggr << 1
+                | match c {
+                    '0' => 0,
+                    '1' => 1,
+                    other => bail!("invalid bit {other:?}"),
+                })
+        })
+    }
+}
+
+impl FromStr for BitReport {
+    type Err = Error;
+
+    /// Parses one binary number per line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let width = s.lines().next().context("report is empty")?.len();
+        ensure!(
+            (1..=64).contains(&width),
+            "line 1: width must be between 1 and 64, not {width}"
+        );
+        let numbers = s
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                Self::parse_number(line, width).with_context(|| format!("line {}", idx + 1))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { width, numbers })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const REPORT: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    #[test]
+    fn rates() {
+        let report: BitReport = REPORT.parse().unwrap();
+        assert_eq!(report.column(0), ColumnStats { zeros: 5, ones: 7 });
+        assert_eq!(report.gamma(), 22);
+        assert_eq!(report.epsilon(), 9);
+    }
+
+    #[test]
+    fn filter() {
+        let report: BitReport = REPORT.parse().unwrap();
+        assert_eq!(report.filter_by(ColumnStats::most_common).unwrap(), 23);
+        assert_eq!(report.filter_by(ColumnStats::least_common).unwrap(), 10);
+        let report = BitReport::new(2, vec![0b00, 0b00]).unwrap();
+        assert!(report.filter_by(|_| true).is_err());
+        let report = BitReport::new(2, vec![0b01, 0b01]).unwrap();
+        assert!(report.filter_by(ColumnStats::most_common).is_err());
+    }
+
+    #[test]
+    fn malformed() {
+        assert!("0101\n011\n".parse::<BitReport>().is_err());
+        assert!("0121\n".parse::<BitReport>().is_err());
+        assert!(BitReport::new(3, vec![0b1000]).is_err());
+    }
+}
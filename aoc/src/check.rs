@@ -0,0 +1,170 @@
+//! Finding problems in puzzle inputs before solving them.
+use std::fmt::{self, Display};
+
+use crate::{
+    groups::groups,
+    parse::{self, Cursor, ParseError},
+};
+
+/// Something wrong with (or fixed in) an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    /// The one based line the problem is on, `None` if it concerns the whole
+    /// input.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(line: impl Into<Option<usize>>, message: impl Display) -> Self {
+        Self {
+            line: line.into(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl From<ParseError> for Problem {
+    fn from(error: ParseError) -> Self {
+        Self::new(
+            error.position.line,
+            format_args!("column {}: {}", error.position.column, error.message),
+        )
+    }
+}
+
+/// Converts Windows line endings, strips trailing whitespace and adds a
+/// missing final line break, reporting every change made.
+///
+/// `trim` controls whether trailing whitespace is stripped, which is not safe
+/// for inputs where it is significant.
+pub fn normalize(input: &str, trim: bool) -> (String, Vec<Problem>) {
+    let mut normalized = String::with_capacity(input.len());
+    let mut fixes = Vec::new();
+    for (idx, line) in input.split_inclusive('\n').enumerate() {
+        let line_number = idx + 1;
+        let mut line = line.strip_suffix('\n').unwrap_or(line);
+        if let Some(stripped) = line.strip_suffix('\r') {
+            fixes.push(Problem::new(line_number, "converted Windows line ending"));
+            line = stripped;
+        }
+        if trim && line.ends_with(char::is_whitespace) {
+            fixes.push(Problem::new(line_number, "removed trailing whitespace"));
+            line = line.trim_end();
+        }
+        normalized.push_str(line);
+        normalized.push('\n');
+    }
+    if !input.is_empty() && !input.ends_with('\n') {
+        fixes.push(Problem::new(None, "added missing final line break"));
+    }
+    (normalized, fixes)
+}
+
+/// Runs `check` on every line, collecting all failures.
+pub fn lines(input: &str, mut check: impl FnMut(&str) -> anyhow::Result<()>) -> Vec<Problem> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            check(line)
+                .err()
+                .map(|error| Problem::new(idx + 1, format_args!("{error:#}")))
+        })
+        .collect()
+}
+
+/// Runs `parser` on every line, collecting all failures.
+pub fn parse_lines<T>(
+    input: &str,
+    mut parser: impl FnMut(&mut Cursor) -> parse::Result<T>,
+) -> Vec<Problem> {
+    input
+        .lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            parse::parse_line(idx + 1, line, &mut parser)
+                .err()
+                .map(Problem::from)
+        })
+        .collect()
+}
+
+/// Runs `check` on every block of lines separated by blank lines, collecting
+/// all failures.
+///
+/// Positions in the [`ParseError`]s are relative to the block and are
+/// translated to the whole input.
+pub fn blocks(input: &str, mut check: impl FnMut(&str) -> Result<(), ParseError>) -> Vec<Problem> {
+    groups(input)
+        .filter_map(|group| {
+            check(group.text).err().map(|mut error| {
+                error.position.line += group.line - 1;
+                error.into()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::ensure;
+
+    use super::*;
+
+    #[test]
+    fn normalize() {
+        let (input, fixes) = super::normalize("a \r\nb\n\nc", true);
+        assert_eq!(input, "a\nb\n\nc\n");
+        assert_eq!(
+            fixes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "line 1: converted Windows line ending",
+                "line 1: removed trailing whitespace",
+                "added missing final line break"
+            ]
+        );
+        assert_eq!(super::normalize("a \n", false), ("a \n".into(), vec![]));
+    }
+
+    #[test]
+    fn collect() {
+        let problems = lines("1\nx\n3\ny\n", |line| {
+            ensure!(line.parse::<u8>().is_ok(), "not a number");
+            Ok(())
+        });
+        assert_eq!(
+            problems,
+            [
+                Problem::new(2, "not a number"),
+                Problem::new(4, "not a number")
+            ]
+        );
+
+        let problems = parse_lines("1\n2x\n", |c| c.unsigned::<u8>());
+        assert_eq!(
+            problems,
+            [Problem::new(
+                2,
+                "column 2: expected end of input, found 'x'"
+            )]
+        );
+
+        let problems = blocks("1\n\n2\nx\n", |block| {
+            crate::parse::parse(block, |c| c.lines(|c| c.unsigned::<u8>()).map(drop))
+        });
+        assert_eq!(
+            problems[0].to_string(),
+            "line 4: column 1: expected number, found 'x'"
+        );
+    }
+}
@@ -0,0 +1,388 @@
+//! Talks to adventofcode.com, or the stand-in from [`site`](crate::site),
+//! politely: inputs are cached forever, puzzle pages are revalidated with
+//! their `ETag`, and requests are spaced out by at least
+//! [`min_interval`](Client::min_interval), even across runs.
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{runner::Part, template::Puzzle};
+
+pub const BASE: &str = "https://adventofcode.com";
+
+/// Identifies the tool and who runs it to the site, as its maintainer asks of
+/// automated requests.
+pub fn user_agent(contact: &str) -> String {
+    format!("aoc/{} (by {contact})", env!("CARGO_PKG_VERSION"))
+}
+
+/// Where the configuration and cache live, following the XDG variables with
+/// the usual fallbacks below `$HOME`.
+fn dir(variable: &str, fallback: &str) -> Result<PathBuf> {
+    match env::var_os(variable) {
+        Some(dir) if !dir.is_empty() => Ok(PathBuf::from(dir).join("aoc")),
+        _ => {
+            let home = env::var_os("HOME").context("HOME is not set")?;
+            Ok(Path::new(&home).join(fallback).join("aoc"))
+        }
+    }
+}
+
+/// The trimmed value of `variable`, or else the contents of the first of
+/// `paths` that exists.
+fn setting(variable: &str, paths: impl IntoIterator<Item = PathBuf>) -> Result<Option<String>> {
+    if let Ok(value) = env::var(variable) {
+        return Ok(Some(value.trim().to_string()));
+    }
+    for path in paths {
+        match fs::read_to_string(&path) {
+            Ok(value) => return Ok(Some(value.trim().to_string())),
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+    Ok(None)
+}
+
+/// Reads the session token from `AOC_SESSION`, `~/.config/aoc/session` or
+/// the `~/.adventofcode.session` that aoc-cli used.
+pub fn session() -> Result<String> {
+    let config = dir("XDG_CONFIG_HOME", ".config")?.join("session");
+    let legacy = env::var_os("HOME").map(|home| Path::new(&home).join(".adventofcode.session"));
+    setting(
+        "AOC_SESSION",
+        [Some(config.clone()), legacy].into_iter().flatten(),
+    )?
+    .with_context(|| {
+        format!(
+            "no session token, set AOC_SESSION or write it to {}",
+            config.display()
+        )
+    })
+}
+
+/// Reads how to reach whoever runs the tool, like an email address or the
+/// repository URL, from `AOC_CONTACT` or `~/.config/aoc/contact`, for the
+/// [`user_agent`].
+pub fn contact() -> Result<String> {
+    let config = dir("XDG_CONFIG_HOME", ".config")?.join("contact");
+    setting("AOC_CONTACT", [config.clone()])?
+        .filter(|contact| !contact.is_empty())
+        .with_context(|| {
+            format!(
+                "no contact for the User-Agent, set AOC_CONTACT to an email address or repository URL or write it to {}",
+                config.display()
+            )
+        })
+}
+
+/// What the site replied to an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooRecent,
+    WrongLevel,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub verdict: Verdict,
+    /// The text of the reply, without markup.
+    pub message: String,
+}
+
+impl Reply {
+    fn parse(html: &str) -> Self {
+        let article = between(html, "<article>", "</article>").unwrap_or(html);
+        let mut message = String::new();
+        let mut in_tag = false;
+        for c in article.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' => in_tag = false,
+                c if !in_tag => message.push(c),
+                _ => {}
+            }
+        }
+        let message = message.trim().to_string();
+        let verdict = [
+            ("That's the right answer", Verdict::Correct),
+            ("That's not the right answer", Verdict::Wrong),
+            ("You gave an answer too recently", Verdict::TooRecent),
+            (
+                "You don't seem to be solving the right level",
+                Verdict::WrongLevel,
+            ),
+        ]
+        .into_iter()
+        .find(|(start, _)| message.starts_with(start))
+        .map_or(Verdict::Unknown, |(_, verdict)| verdict);
+        Self { verdict, message }
+    }
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let (_, rest) = text.split_once(start)?;
+    Some(rest.split_once(end).map_or(rest, |(inner, _)| inner))
+}
+
+/// Converts a puzzle page into the markdown of a day's `README.md`.
+pub fn readme(html: &str) -> String {
+    let main = between(html, "<main>", "</main>").unwrap_or(html);
+    let mut markdown = html2md::parse_html(main);
+    markdown.push('\n');
+    markdown
+}
+
+#[derive(Debug)]
+pub struct Client {
+    base: String,
+    session: String,
+    /// Where inputs, pages and the time of the last request are kept.
+    pub cache: PathBuf,
+    pub min_interval: Duration,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// A client sending `session` and naming `contact` in its
+    /// [`user_agent`].
+    pub fn new(session: impl Into<String>, contact: &str) -> Result<Self> {
+        Ok(Self {
+            base: BASE.to_string(),
+            session: session.into(),
+            cache: dir("XDG_CACHE_HOME", ".cache")?,
+            min_interval: Duration::from_secs(5),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(&user_agent(contact))
+                .build(),
+        })
+    }
+
+    /// A client for the site at `AOC_URL`, or the real one if it is unset,
+    /// with the [`session`] and [`contact`].
+    pub fn from_env() -> Result<Self> {
+        let mut client = Self::new(session()?, &contact()?)?;
+        if let Ok(base) = env::var("AOC_URL") {
+            client = client.with_base(base);
+        }
+        Ok(client)
+    }
+
+    /// Talks to the site at `base` instead, caching separately from it.
+    pub fn with_base(mut self, base: impl Into<String>) -> Self {
+        self.base = base.into().trim_end_matches('/').to_string();
+        let host: String = self
+            .base
+            .split_once("://")
+            .map_or(self.base.as_str(), |(_, host)| host)
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.cache = self.cache.join(host);
+        self
+    }
+
+    fn url(&self, puzzle: Puzzle, path: &str) -> String {
+        format!("{}/{}/day/{}{path}", self.base, puzzle.year, puzzle.day)
+    }
+
+    fn cached(&self, puzzle: Puzzle, name: &str) -> PathBuf {
+        self.cache.join(puzzle.dir()).join(name)
+    }
+
+    /// Waits until `min_interval` passed since the last request of any
+    /// client sharing the cache, then records this one.
+    fn throttle(&self) -> Result<()> {
+        let path = self.cache.join("last-request");
+        let now = SystemTime::now();
+        let last = fs::read_to_string(&path)
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+        if let Some(wait) = last
+            .and_then(|last| (last + self.min_interval).duration_since(now).ok())
+            .filter(|wait| !wait.is_zero())
+        {
+            thread::sleep(wait);
+        }
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        write(&path, &millis.to_string())
+    }
+
+    fn get(&self, url: &str) -> ureq::Request {
+        self.agent
+            .get(url)
+            .set("Cookie", &format!("session={}", self.session))
+    }
+
+    /// The puzzle input, downloaded only the first time.
+    pub fn input(&self, puzzle: Puzzle) -> Result<String> {
+        let path = self.cached(puzzle, "input");
+        if let Ok(input) = fs::read_to_string(&path) {
+            return Ok(input);
+        }
+        self.throttle()?;
+        let url = self.url(puzzle, "/input");
+        let input = self
+            .get(&url)
+            .call()
+            .with_context(|| format!("downloading {url}"))?
+            .into_string()
+            .with_context(|| format!("downloading {url}"))?;
+        write(&path, &input)?;
+        Ok(input)
+    }
+
+    /// The HTML of the puzzle page, downloaded again only if it changed.
+    pub fn page(&self, puzzle: Puzzle) -> Result<String> {
+        let path = self.cached(puzzle, "puzzle.html");
+        let etag_path = self.cached(puzzle, "puzzle.etag");
+        let cached = fs::read_to_string(&path).ok();
+        let etag = fs::read_to_string(&etag_path).ok();
+        self.throttle()?;
+        let url = self.url(puzzle, "");
+        let mut request = self.get(&url);
+        if let (Some(_), Some(etag)) = (&cached, &etag) {
+            request = request.set("If-None-Match", etag);
+        }
+        let response = request
+            .call()
+            .with_context(|| format!("downloading {url}"))?;
+        if response.status() == 304 {
+            if let Some(cached) = cached {
+                return Ok(cached);
+            }
+        }
+        let etag = response.header("ETag").map(str::to_string);
+        let page = response
+            .into_string()
+            .with_context(|| format!("downloading {url}"))?;
+        write(&path, &page)?;
+        match etag {
+            Some(etag) => write(&etag_path, &etag)?,
+            None => {
+                let _ = fs::remove_file(&etag_path);
+            }
+        }
+        Ok(page)
+    }
+
+    pub fn submit(&self, puzzle: Puzzle, part: Part, answer: &str) -> Result<Reply> {
+        self.throttle()?;
+        let url = self.url(puzzle, "/answer");
+        let level = match part {
+            Part::One => "1",
+            Part::Two => "2",
+        };
+        let html = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", level), ("answer", answer)])
+            .with_context(|| format!("submitting to {url}"))?
+            .into_string()
+            .with_context(|| format!("submitting to {url}"))?;
+        Ok(Reply::parse(&html))
+    }
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+    }
+    fs::write(path, contents).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reply() {
+        let reply = Reply::parse(
+            "<main>\n<article><p>That's not the right answer.  <a href=\"/\">[Return]</a></p></article>\n</main>",
+        );
+        assert_eq!(reply.verdict, Verdict::Wrong);
+        assert_eq!(reply.message, "That's not the right answer.  [Return]");
+        assert_eq!(Reply::parse("<p>Maintenance</p>").verdict, Verdict::Unknown);
+    }
+
+    #[test]
+    fn user_agent() {
+        assert_eq!(
+            super::user_agent("https://example.com/aoc"),
+            format!(
+                "aoc/{} (by https://example.com/aoc)",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+    }
+
+    #[test]
+    fn readme() {
+        let html = "<html><main><article><h2>--- Day 1: Calorie Counting ---</h2><p>Count <em>calories</em>.</p></article></main></html>";
+        let readme: crate::readme::Readme = super::readme(html).parse().unwrap();
+        assert_eq!((readme.day, readme.title.as_str()), (1, "Calorie Counting"));
+    }
+
+    /// Fetches and submits against the stand-in site.
+    #[test]
+    #[cfg(feature = "server")]
+    fn stand_in() {
+        use std::{net::TcpListener, time::Instant};
+
+        use crate::{site::Site, status};
+
+        let root = env::temp_dir().join(format!("aoc-client-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let fixture = root.join("fixtures/2022/1");
+        fs::create_dir_all(&fixture).unwrap();
+        fs::write(fixture.join("puzzle.html"), "<main>one</main>").unwrap();
+        fs::write(fixture.join("puzzle-1.html"), "<main>two</main>").unwrap();
+        fs::write(fixture.join("input"), "1000\n").unwrap();
+        fs::write(fixture.join(status::LEDGER), "one 3000\n").unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut site = Site::new(root.join("fixtures"));
+        site.cooldown = Duration::ZERO;
+        thread::spawn(move || site.serve(&listener));
+
+        let mut client = Client::new("abc", "me@example.com").unwrap();
+        client.cache = root.join("cache");
+        client.min_interval = Duration::from_millis(100);
+        let client = client.with_base(format!("http://{addr}/"));
+        let host = format!("127_0_0_1_{}", addr.port());
+        assert_eq!(client.cache, root.join("cache").join(host));
+        let puzzle = Puzzle::new(2022, 1).unwrap();
+
+        let start = Instant::now();
+        assert_eq!(client.input(puzzle).unwrap(), "1000\n");
+        fs::remove_file(fixture.join("input")).unwrap();
+        assert_eq!(client.input(puzzle).unwrap(), "1000\n");
+
+        assert_eq!(client.page(puzzle).unwrap(), "<main>one</main>");
+        assert!(client.cached(puzzle, "puzzle.etag").exists());
+        assert_eq!(client.page(puzzle).unwrap(), "<main>one</main>");
+        assert!(start.elapsed() >= 2 * client.min_interval);
+
+        let reply = client.submit(puzzle, Part::One, "2000").unwrap();
+        assert_eq!(reply.verdict, Verdict::Wrong);
+        let reply = client.submit(puzzle, Part::One, "3000").unwrap();
+        assert_eq!(reply.verdict, Verdict::Correct);
+        assert_eq!(client.page(puzzle).unwrap(), "<main>two</main>");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,56 @@
+//! Stable stand-ins for the nightly iterator and slice methods the solutions
+//! were first written with.
+use std::{array, collections::VecDeque};
+
+/// Overlapping windows of `N` items of any iterator, like
+/// `<[T]>::array_windows`, which is only stable since Rust 1.94.
+pub fn windows<const N: usize, I>(iter: I) -> Windows<I::IntoIter, N>
+where
+    I: IntoIterator,
+    I::Item: Clone,
+{
+    assert!(N != 0, "window size must be non-zero");
+    Windows {
+        iter: iter.into_iter(),
+        window: VecDeque::with_capacity(N),
+    }
+}
+
+/// The iterator returned by [`windows`].
+#[derive(Debug, Clone)]
+pub struct Windows<I: Iterator, const N: usize> {
+    iter: I,
+    window: VecDeque<I::Item>,
+}
+
+impl<I, const N: usize> Iterator for Windows<I, N>
+where
+    I: Iterator,
+    I::Item: Clone,
+{
+    type Item = [I::Item; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == N {
+            self.window.pop_front();
+        }
+        while self.window.len() < N {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(array::from_fn(|idx| self.window[idx].clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sliding() {
+        let sliding: Vec<_> = windows::<3, _>(1..=5).collect();
+        assert_eq!(sliding, [[1, 2, 3], [2, 3, 4], [3, 4, 5]]);
+        assert_eq!(windows::<3, _>(1..=2).next(), None);
+        let chars: Vec<_> = windows::<2, _>("abc".chars()).collect();
+        assert_eq!(chars, [['a', 'b'], ['b', 'c']]);
+    }
+}
@@ -0,0 +1,259 @@
+//! Seeded random puzzle inputs, for finding panics and slow paths that the
+//! one real input does not hit.
+use std::{
+    any::Any,
+    fmt::{self, Display},
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Result};
+use rand::SeedableRng;
+pub use rand::{seq::SliceRandom, Rng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{check::Problem, runner::Part};
+
+/// The random number generator inputs are generated from.
+///
+/// ChaCha produces the same numbers for a seed on every platform, so a seed
+/// is enough to reproduce an input.
+pub type Random = ChaCha8Rng;
+
+/// Produces a valid input of roughly `size` units, what a unit is (lines,
+/// monkeys, rows, ...) is up to the day.
+pub type Generator = fn(&mut Random, size: usize) -> String;
+
+/// Runs `generator` with a random number generator seeded with `seed`.
+pub fn generate(generator: Generator, seed: u64, size: usize) -> String {
+    generator(&mut Random::seed_from_u64(seed), size)
+}
+
+/// A random lowercase word with a length in `len`.
+pub fn word(rng: &mut Random, len: Range<usize>) -> String {
+    let len = rng.gen_range(len);
+    (0..len).map(|_| rng.gen_range('a'..='z')).collect()
+}
+
+/// A generated input that could not be solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub seed: u64,
+    pub message: String,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seed {}: {}", self.seed, self.message)
+    }
+}
+
+/// The part that took the longest to solve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub seed: u64,
+    pub part: Part,
+    pub elapsed: Duration,
+}
+
+/// The result of solving many generated inputs.
+#[derive(Debug, Clone, Default)]
+pub struct Stress {
+    pub size: usize,
+    pub inputs: usize,
+    pub failures: Vec<Failure>,
+    pub slowest: Option<Timing>,
+}
+
+impl Stress {
+    /// Solves `input` with `solve`, recording a failure or the time taken.
+    fn solve<T>(&mut self, seed: u64, part: Part, input: &str, solve: impl Fn(&str) -> Result<T>) {
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| solve(input).map(drop)));
+        let elapsed = start.elapsed();
+        let message = match result {
+            Ok(Ok(())) => None,
+            Ok(Err(error)) => Some(format!("part {part:?} failed: {error:#}")),
+            Err(payload) => Some(format!(
+                "part {part:?} panicked: {}",
+                panic_message(payload)
+            )),
+        };
+        if let Some(message) = message {
+            self.failures.push(Failure { seed, message });
+        } else if self.slowest.is_none_or(|slowest| slowest.elapsed < elapsed) {
+            self.slowest = Some(Timing {
+                seed,
+                part,
+                elapsed,
+            });
+        }
+    }
+
+    /// Fails listing every failure, if there are any.
+    pub fn check(&self) -> Result<()> {
+        if !self.failures.is_empty() {
+            let failures: Vec<_> = self.failures.iter().map(ToString::to_string).collect();
+            bail!(
+                "{} of {} inputs failed:\n{}",
+                self.failures.len(),
+                self.inputs,
+                failures.join("\n")
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Display for Stress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "solved {} of {} inputs of size {}",
+            self.inputs - self.failures.len(),
+            self.inputs,
+            self.size
+        )?;
+        if let Some(Timing {
+            seed,
+            part,
+            elapsed,
+        }) = self.slowest
+        {
+            write!(
+                f,
+                ", slowest was part {part:?} of seed {seed} in {elapsed:?}"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".into(),
+        },
+    }
+}
+
+/// Generates an input of `size` for every seed in `seeds`, checks it with
+/// `validate` and solves both parts, recording errors, panics and the
+/// slowest part.
+pub fn stress<A, B>(
+    generator: Generator,
+    validate: impl Fn(&str) -> Vec<Problem>,
+    one: impl Fn(&str) -> Result<A>,
+    two: impl Fn(&str) -> Result<B>,
+    seeds: Range<u64>,
+    size: usize,
+) -> Stress {
+    let mut stress = Stress {
+        size,
+        ..Stress::default()
+    };
+    for seed in seeds {
+        stress.inputs += 1;
+        let input = generate(generator, seed, size);
+        if let Some(problem) = validate(&input).first() {
+            stress.failures.push(Failure {
+                seed,
+                message: format!("generated an invalid input: {problem}"),
+            });
+            continue;
+        }
+        stress.solve(seed, Part::One, &input, &one);
+        stress.solve(seed, Part::Two, &input, &two);
+    }
+    stress
+}
+
+/// Defines a `generated` test that [`stress`]es the day with 20 seeds and
+/// inputs of size 100, or the given `seeds` and `size`, failing on any error,
+/// panic or invalid input.
+#[macro_export]
+macro_rules! stress_test {
+    ($generate:expr, $validate:expr, $one:expr, $two:expr $(,)?) => {
+        $crate::stress_test!($generate, $validate, $one, $two, 0..20, 100);
+    };
+    ($generate:expr, $validate:expr, $one:expr, $two:expr, $seeds:expr, $size:expr $(,)?) => {
+        #[test]
+        fn generated() {
+            $crate::generate::stress($generate, $validate, $one, $two, $seeds, $size)
+                .check()
+                .unwrap();
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::ensure;
+
+    use super::*;
+
+    fn numbers(rng: &mut Random, size: usize) -> String {
+        (0..size)
+            .map(|_| format!("{}\n", rng.gen_range(0..100)))
+            .collect()
+    }
+
+    #[test]
+    fn reproducible() {
+        assert_eq!(generate(numbers, 7, 10), generate(numbers, 7, 10));
+        assert_ne!(generate(numbers, 7, 10), generate(numbers, 8, 10));
+        assert_eq!(generate(numbers, 7, 10).lines().count(), 10);
+        let word = word(&mut Random::seed_from_u64(0), 2..5);
+        assert!((2..5).contains(&word.len()) && word.chars().all(|c| c.is_ascii_lowercase()));
+    }
+
+    #[test]
+    fn failures() {
+        let validate = |_: &str| Vec::new();
+        let sum = |input: &str| -> Result<u32> {
+            let mut sum = 0;
+            for line in input.lines() {
+                sum += line.parse::<u32>()?;
+            }
+            Ok(sum)
+        };
+        let stress = stress(numbers, validate, sum, sum, 0..5, 10);
+        stress.check().unwrap();
+        assert_eq!(stress.inputs, 5);
+        assert!(stress.slowest.is_some());
+
+        let picky = |input: &str| -> Result<u32> {
+            ensure!(!input.contains("\n7\n"), "found a 7");
+            Ok(0)
+        };
+        let panicky = |input: &str| -> Result<u32> {
+            assert!(!input.contains("\n9\n"), "found a 9");
+            Ok(0)
+        };
+        let stress = super::stress(numbers, validate, picky, panicky, 0..50, 20);
+        assert!(stress
+            .failures
+            .iter()
+            .any(|f| f.message == "part One failed: found a 7"));
+        assert!(stress
+            .failures
+            .iter()
+            .any(|f| f.message == "part Two panicked: found a 9"));
+        assert!(stress.check().is_err());
+
+        let stress = super::stress(numbers, |_| vec![Problem::new(1, "bad")], sum, sum, 0..1, 1);
+        assert_eq!(
+            stress.failures[0].to_string(),
+            "seed 0: generated an invalid input: line 1: bad"
+        );
+    }
+
+    fn sum(input: &str) -> Result<usize> {
+        Ok(input.len())
+    }
+
+    crate::stress_test!(numbers, |_| Vec::new(), sum, sum, 0..3, 10);
+}
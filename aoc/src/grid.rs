@@ -0,0 +1,192 @@
+//! Rectangular grids with one cell per character of the input.
+use std::{
+    fmt::{self, Display},
+    ops::{Index, IndexMut},
+};
+
+use crate::parse::{self, ParseError, Position};
+
+/// A column and a row, counted from the top left.
+pub type Pos = (usize, usize);
+
+/// Cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A `width` by `height` grid with the cell at each position from `cell`.
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(&mut cell)
+            .collect();
+        Self { width, cells }
+    }
+
+    /// Parses one row per line, converting each character with `cell`.
+    ///
+    /// Fails on characters `cell` rejects, rows of differing width and an
+    /// empty input.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Option<T>) -> parse::Result<Self> {
+        let error = |line, column, message: String| ParseError {
+            position: Position { line, column },
+            message,
+        };
+        let mut width = None;
+        let mut cells = Vec::new();
+        for (idx, line) in input.lines().enumerate() {
+            let before = cells.len();
+            for (column, c) in line.chars().enumerate() {
+                let value = cell(c)
+                    .ok_or_else(|| error(idx + 1, column + 1, format!("invalid cell {c:?}")))?;
+                cells.push(value);
+            }
+            let len = cells.len() - before;
+            let width = *width.get_or_insert(len);
+            if len != width {
+                return Err(error(
+                    idx + 1,
+                    len.min(width) + 1,
+                    format!("row has {len} cells, expected {width}"),
+                ));
+            }
+        }
+        match width {
+            Some(width) if width > 0 => Ok(Self { width, cells }),
+            _ => Err(error(1, 1, "grid is empty".into())),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x < self.width && y < self.height()
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.0 + pos.1 * self.width])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.cells[pos.0 + pos.1 * self.width])
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height()).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// The positions above, left of, right of and below `pos` that lie
+    /// within the grid.
+    pub fn neighbours(&self, (x, y): Pos) -> impl Iterator<Item = Pos> + '_ {
+        [
+            y.checked_sub(1).map(|y| (x, y)),
+            x.checked_sub(1).map(|x| (x, y)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&pos| self.contains(pos))
+    }
+
+    /// The first position, row by row, whose cell matches `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.positions().find(|&pos| predicate(&self[pos]))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the grid"))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    /// Writes one row per line, the way the puzzle input does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digit(c: char) -> Option<u32> {
+        c.to_digit(10)
+    }
+
+    #[test]
+    fn parse() {
+        let grid = Grid::parse("123\n456\n", digit).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.find(|&cell| cell > 4), Some((1, 1)));
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(
+            grid.map(|&cell| cell * 2).rows().last(),
+            Some(&[8, 10, 12][..])
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let error = Grid::parse("123\n4x6\n", digit).unwrap_err();
+        assert_eq!(error.to_string(), "2:2: invalid cell 'x'");
+        let error = Grid::parse("123\n45\n", digit).unwrap_err();
+        assert_eq!(error.to_string(), "2:3: row has 2 cells, expected 3");
+        assert!(Grid::parse("", digit).is_err());
+        assert!(Grid::parse("\n\n", digit).is_err());
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::from_fn(3, 3, |(x, y)| x + y);
+        assert_eq!(grid[(2, 1)], 3);
+        let corner: Vec<_> = grid.neighbours((0, 0)).collect();
+        assert_eq!(corner, [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours((1, 1)).count(), 4);
+        assert_eq!(grid.positions().count(), 9);
+    }
+}
@@ -0,0 +1,68 @@
+//! Inputs made of blocks of lines separated by blank lines.
+
+/// A block of consecutive non-blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Group<'a> {
+    /// The one based line number of the first line of the block.
+    pub line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Group<'a> {
+    /// The lines of the block together with their one based line numbers.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let line = self.line;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(idx, text)| (line + idx, text))
+    }
+}
+
+/// Splits `input` into blocks of lines separated by one or more blank lines.
+///
+/// Lines only containing whitespace count as blank.
+pub fn groups(input: &str) -> impl Iterator<Item = Group<'_>> {
+    let mut lines = input.lines().enumerate().peekable();
+    std::iter::from_fn(move || {
+        while lines.next_if(|(_, line)| line.trim().is_empty()).is_some() {}
+        let (start, first) = lines.next()?;
+        let mut last = first;
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+            last = line;
+        }
+        let offset = first.as_ptr() as usize - input.as_ptr() as usize;
+        let end = last.as_ptr() as usize - input.as_ptr() as usize + last.len();
+        Some(Group {
+            line: start + 1,
+            text: &input[offset..end],
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split() {
+        let input = "\n1\n2\n\n  \n3\r\n\n4\n5\n";
+        let groups: Vec<_> = groups(input).collect();
+        assert_eq!(
+            groups,
+            [
+                Group {
+                    line: 2,
+                    text: "1\n2"
+                },
+                Group { line: 6, text: "3" },
+                Group {
+                    line: 8,
+                    text: "4\n5"
+                }
+            ]
+        );
+        assert_eq!(groups[2].lines().collect::<Vec<_>>(), [(8, "4"), (9, "5")]);
+        assert_eq!(super::groups("").count(), 0);
+    }
+}
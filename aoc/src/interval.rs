@@ -0,0 +1,254 @@
+//! Half-open intervals and sets of them.
+use std::{
+    ops::{Add, Range, Sub},
+    str::FromStr,
+};
+
+use anyhow::{ensure, Context, Error, Result};
+use num_traits::{CheckedAdd, One};
+
+/// Relations between two half-open ranges.
+pub trait Interval<T> {
+    /// Whether `other` lies completely within `self`.
+    fn contains_range(&self, other: &Range<T>) -> bool;
+    /// Whether `self` and `other` share at least one value.
+    fn overlaps(&self, other: &Range<T>) -> bool;
+    /// The values shared by `self` and `other`, if any.
+    fn intersection(&self, other: &Range<T>) -> Option<Range<T>>;
+}
+
+impl<T: Ord + Copy> Interval<T> for Range<T> {
+    fn contains_range(&self, other: &Range<T>) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    fn overlaps(&self, other: &Range<T>) -> bool {
+        self.start.max(other.start) < self.end.min(other.end)
+    }
+
+    fn intersection(&self, other: &Range<T>) -> Option<Range<T>> {
+        let range = self.start.max(other.start)..self.end.min(other.end);
+        (!range.is_empty()).then_some(range)
+    }
+}
+
+/// Sorted, disjoint and non-adjacent ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Ord + Copy> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The merged ranges in ascending order.
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let idx = self.ranges.partition_point(|range| range.end <= *value);
+        self.ranges
+            .get(idx)
+            .is_some_and(|range| range.contains(value))
+    }
+
+    /// Adds `range`, merging it with every range it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let start = self.ranges.partition_point(|r| r.end < range.start);
+        let end = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if start < end {
+            self.ranges[start].start.min(range.start)..self.ranges[end - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(start..end, [merged]);
+    }
+
+    /// All values contained in `self` or `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        union.extend(other.ranges.iter().cloned());
+        union
+    }
+
+    /// All values contained in both `self` and `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            ranges.extend(x.intersection(y));
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    /// All values contained in every one of `sets`.
+    ///
+    /// Returns an empty set if `sets` is empty.
+    pub fn intersect_all(sets: impl IntoIterator<Item = Self>) -> Self {
+        sets.into_iter()
+            .reduce(|aggr, set| aggr.intersection(&set))
+            .unwrap_or_default()
+    }
+
+    /// The number of values covered.
+    pub fn len(&self) -> T
+    where
+        T: Sub<Output = T> + Add<Output = T> + Default,
+    {
+        self.ranges
+            .iter()
+            .fold(T::default(), |aggr, range| aggr + (range.end - range.start))
+    }
+}
+
+impl<T: Ord + Copy> Extend<Range<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+impl<T: Ord + Copy> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+/// Merges overlapping and adjacent ranges, returning them in ascending order.
+pub fn merge<T: Ord + Copy>(ranges: impl IntoIterator<Item = Range<T>>) -> Vec<Range<T>> {
+    ranges.into_iter().collect::<IntervalSet<_>>().ranges
+}
+
+/// Parses an inclusive range `a-b` into the half-open `a..b + 1`, failing if
+/// `b` is less than `a` or `b + 1` does not fit into `T`.
+///
+/// Both bounds may be negative, e.g. `-3--1`.
+pub fn parse_range<T>(s: &str) -> Result<Range<T>>
+where
+    T: FromStr + CheckedAdd + One + Ord,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let split = s
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c == '-')
+        .map(|(idx, _)| idx)
+        .with_context(|| format!("range {s:?} is not formatted as `<from>-<to>`"))?;
+    let (from, to) = (&s[..split], &s[split + 1..]);
+    let first: T = from
+        .parse()
+        .with_context(|| format!("invalid range start {from:?}"))?;
+    let last: T = to
+        .parse()
+        .with_context(|| format!("invalid range end {to:?}"))?;
+    ensure!(first <= last, "range {s:?} ends before it starts");
+    let end = last
+        .checked_add(&T::one())
+        .with_context(|| format!("range end {to} is too large"))?;
+    Ok(first..end)
+}
+
+/// Parses a pair of inclusive ranges `a-b,c-d`.
+pub fn parse_pair<T>(s: &str) -> Result<(Range<T>, Range<T>)>
+where
+    T: FromStr + CheckedAdd + One + Ord,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let (a, b) = s
+        .split_once(',')
+        .with_context(|| format!("pair {s:?} is not formatted as `<range>,<range>`"))?;
+    Ok((parse_range(a)?, parse_range(b)?))
+}
+
+/// Parses every line of `input` with [`parse_pair`], naming the line on
+/// failure.
+pub fn parse_pairs<T>(input: &str) -> Result<Vec<(Range<T>, Range<T>)>>
+where
+    T: FromStr + CheckedAdd + One + Ord,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| parse_pair(line).with_context(|| format!("line {}: {line:?}", idx + 1)))
+        .collect::<Result<_, Error>>()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn relations() {
+        assert!((2..9).contains_range(&(3..8)));
+        assert!(!(3..8).contains_range(&(2..9)));
+        assert!((5..8).overlaps(&(7..10)));
+        assert!(!(2..5).overlaps(&(5..8)));
+        assert_eq!((2..6).intersection(&(4..9)), Some(4..6));
+        assert_eq!((2..4).intersection(&(6..9)), None);
+    }
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn set() {
+        let set: IntervalSet<i32> = [5..8, 1..3, 3..4, 10..12, 7..9].into_iter().collect();
+        assert_eq!(set.ranges(), [1..4, 5..9, 10..12]);
+        assert_eq!(set.len(), 9);
+        assert!(set.contains(&3) && !set.contains(&4) && set.contains(&11));
+
+        let other: IntervalSet<i32> = [2..6, 8..11].into_iter().collect();
+        assert_eq!(
+            set.intersection(&other).ranges(),
+            [2..4, 5..6, 8..9, 10..11]
+        );
+        assert_eq!(set.union(&other).ranges(), [1..12]);
+        assert_eq!(
+            IntervalSet::intersect_all([set, other, [0..3].into_iter().collect()]).ranges(),
+            [2..3]
+        );
+        assert_eq!(merge([4..6, 0..2, 1..3]), [0..3, 4..6]);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_pair::<u32>("2-4,6-8").unwrap(), (2..5, 6..9));
+        assert_eq!(parse_range::<i32>("-3--1").unwrap(), -3..0);
+        assert!(parse_pair::<u32>("2-4;6-8").is_err());
+        assert!(parse_range::<u32>("2-x").is_err());
+        assert_eq!(parse_range::<u32>("3-3").unwrap(), 3..4);
+        let err = parse_range::<u32>("5-3").unwrap_err();
+        assert_eq!(err.to_string(), "range \"5-3\" ends before it starts");
+        assert_eq!(parse_range::<u32>("0-4294967294").unwrap(), 0..u32::MAX);
+        let err = parse_pair::<u32>("4294967295-4294967295,1-2").unwrap_err();
+        assert_eq!(format!("{err:#}"), "range end 4294967295 is too large");
+        let err = parse_pairs::<u32>("1-2,3-4\n1-2,3").unwrap_err();
+        assert!(format!("{err:#}").starts_with("line 2"));
+    }
+}
@@ -0,0 +1,40 @@
+//! Helpers shared between the solutions of the different days.
+pub mod answer;
+pub mod args;
+pub mod bits;
+pub mod check;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod compat;
+pub mod generate;
+pub mod grid;
+pub mod groups;
+pub mod interval;
+pub mod log;
+pub mod parse;
+pub mod profile;
+pub mod readme;
+pub mod release;
+pub mod runner;
+#[cfg(feature = "server")]
+pub mod site;
+pub mod status;
+pub mod template;
+pub mod top;
+
+use anyhow::Result;
+
+use crate::check::Problem;
+
+/// Feeds arbitrary `input` to a day's `validate`, `one` and `two` for its
+/// fuzz target, which fails on any panic.
+pub fn fuzz<A, B>(
+    input: &str,
+    validate: impl Fn(&str) -> Vec<Problem>,
+    one: impl Fn(&str) -> Result<A>,
+    two: impl Fn(&str) -> Result<B>,
+) {
+    validate(input);
+    let _ = one(input);
+    let _ = two(input);
+}
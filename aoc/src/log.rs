@@ -0,0 +1,196 @@
+//! Diagnostics for the solutions, written to stderr so they never end up in
+//! an answer, and hidden unless enabled.
+//!
+//! What is shown is set with `AOC_LOG`, like `debug` or `warn,y2022d7=trace`
+//! for one day, where the target is the module path of the message. The
+//! runner's `--verbose` shows debug messages of every target.
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    sync::RwLock,
+};
+
+use anyhow::{bail, Context, Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub const ALL: [Self; 5] = [
+        Self::Error,
+        Self::Warn,
+        Self::Info,
+        Self::Debug,
+        Self::Trace,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL.into_iter().find(|level| level.name() == s) {
+            Some(level) => Ok(level),
+            None => bail!("unknown log level {s:?}"),
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The most verbose level shown, by default and for some targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    pub default: Level,
+    pub targets: Vec<(String, Level)>,
+}
+
+impl Filter {
+    const DEFAULT: Self = Self {
+        default: Level::Warn,
+        targets: Vec::new(),
+    };
+
+    /// Whether messages of `level` from `target` are shown, going by the
+    /// longest target that `target` is or is inside of.
+    pub fn enabled(&self, level: Level, target: &str) -> bool {
+        let max = self
+            .targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |&(_, level)| level);
+        level <= max
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl FromStr for Filter {
+    type Err = Error;
+
+    /// Parses comma separated levels like `debug` and targets like
+    /// `y2022d7=trace`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Self::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((target, level)) => filter.targets.push((
+                    target.to_string(),
+                    level.parse().with_context(|| format!("in {directive:?}"))?,
+                )),
+                None => filter.default = directive.parse()?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+static FILTER: RwLock<Filter> = RwLock::new(Filter::DEFAULT);
+
+pub fn set_filter(filter: Filter) {
+    *FILTER.write().unwrap_or_else(|error| error.into_inner()) = filter;
+}
+
+pub fn enabled(level: Level, target: &str) -> bool {
+    FILTER
+        .read()
+        .unwrap_or_else(|error| error.into_inner())
+        .enabled(level, target)
+}
+
+/// Writes the message, if enabled. Use the macros like [`debug!`] instead.
+#[doc(hidden)]
+pub fn write(level: Level, target: &str, message: fmt::Arguments) {
+    if enabled(level, target) {
+        eprintln!("{level} {target}: {message}");
+    }
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        $crate::log::write($crate::log::Level::Error, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::log::write($crate::log::Level::Warn, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::log::write($crate::log::Level::Info, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log::write($crate::log::Level::Debug, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log::write($crate::log::Level::Trace, module_path!(), format_args!($($arg)+))
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn filter() {
+        let filter: Filter = "info, y2022d7=trace,y2022d7::parse=error".parse().unwrap();
+        assert_eq!(filter.default, Level::Info);
+        assert!(filter.enabled(Level::Info, "aoc::runner"));
+        assert!(!filter.enabled(Level::Debug, "aoc::runner"));
+        assert!(filter.enabled(Level::Trace, "y2022d7"));
+        assert!(filter.enabled(Level::Trace, "y2022d7::tree"));
+        assert!(!filter.enabled(Level::Warn, "y2022d7::parse"));
+        assert!(!filter.enabled(Level::Debug, "y2022d70"));
+
+        assert_eq!(Filter::default(), "".parse().unwrap());
+        assert!(!Filter::default().enabled(Level::Info, "y2022d1"));
+        let error = "y2022d7=loud".parse::<Filter>().unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "in \"y2022d7=loud\": unknown log level \"loud\""
+        );
+    }
+}
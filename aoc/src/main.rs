@@ -0,0 +1,185 @@
+//! Tools for working on the puzzles, run from the repository root.
+//!
+//! `cargo run --manifest-path aoc/Cargo.toml -- <command>`
+//!
+//! - `new [<year> <day>] [--kind <skeleton>] [--wait]` creates the crate for
+//!   a day, by default the next one to unlock. With `--wait`, it then waits
+//!   for the puzzle to unlock and fetches it.
+//! - `status [--year <year>] [--test]` shows the stars, test results and
+//!   runtimes of every day, running the tests first with `--test`.
+//! - `fetch <year> <day> [--dir <dir>]` downloads the `README.md` and
+//!   `input` of a day into its directory.
+//! - `submit <year> <day> <part> <answer> [--readme <path>]` submits an
+//!   answer, failing unless it is right, and then refreshes the README.
+//! - `serve <fixtures> [--port <port>] [--cooldown <seconds>]` serves the
+//!   fixtures like adventofcode.com does, see [`aoc::site`].
+//!
+//! `fetch`, `submit` and `new --wait` read the session token and a contact
+//! for the User-Agent as described in [`aoc::client::session`] and
+//! [`aoc::client::contact`], and talk to `AOC_URL` instead of the real site
+//! if it is set.
+use std::{
+    env, fs,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use aoc::{
+    args::Args,
+    client::{self, Client, Verdict},
+    release::{self, Backoff, Clock, SystemClock},
+    runner::Part,
+    site::Site,
+    status,
+    template::{self, Puzzle, Skeleton},
+};
+
+fn main() -> Result<()> {
+    let mut args = Args::from_env();
+    match args.positional().as_deref() {
+        Some("new") => new(args),
+        Some("status") => status(args),
+        Some("fetch") => fetch(args),
+        Some("submit") => submit(args),
+        Some("serve") => serve(args),
+        Some(command) => bail!("unknown command {command:?}"),
+        None => bail!("expected a command: new, status, fetch, submit, serve"),
+    }
+}
+
+/// The required positional argument `name`.
+fn positional<T>(args: &mut Args, name: &str) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    args.positional()
+        .with_context(|| format!("expected the {name}"))?
+        .parse()
+        .with_context(|| format!("invalid {name}"))
+}
+
+fn new(mut args: Args) -> Result<()> {
+    let clock = SystemClock;
+    let puzzle = match args.positional() {
+        Some(year) => Puzzle::new(
+            year.parse().context("invalid year")?,
+            positional(&mut args, "day")?,
+        )?,
+        None => release::next(clock.now()),
+    };
+    let skeleton = match args.value::<String>("--kind")? {
+        Some(kind) => kind.parse()?,
+        None => Skeleton::default(),
+    };
+    let wait = args.flag("--wait");
+    args.finish()?;
+    let root = env::current_dir().context("finding the repository root")?;
+    // Created before waiting, so that an existing day fails right away
+    // instead of having its README and input overwritten.
+    let dir = template::create(&root, puzzle, skeleton)?;
+    let name = dir.strip_prefix(&root).unwrap_or(&dir);
+    eprintln!(
+        "created {} from the {skeleton} skeleton",
+        Path::new(name).display()
+    );
+    if wait {
+        let client = Client::from_env()?;
+        let mut waited = false;
+        release::wait(&clock, puzzle, |left| {
+            waited = true;
+            eprint!(
+                "\r{} unlocks in {} ",
+                puzzle.name(),
+                release::countdown(left)
+            );
+        });
+        if waited {
+            eprintln!();
+        }
+        release::retry(&clock, Backoff::default(), || {
+            download(&client, puzzle, &dir)
+        })?;
+    }
+    Ok(())
+}
+
+fn status(mut args: Args) -> Result<()> {
+    let year = args.value::<u16>("--year")?;
+    let test = args.flag("--test");
+    args.finish()?;
+    let root = env::current_dir().context("finding the repository root")?;
+    let mut days = status::scan(&root)?;
+    days.retain(|day| year.is_none_or(|year| day.year == year));
+    if test {
+        for day in &mut days {
+            eprintln!("testing {}/{}", day.year, day.day);
+            status::test(day)?;
+        }
+    }
+    print!("{}", status::render(&days));
+    Ok(())
+}
+
+fn fetch(mut args: Args) -> Result<()> {
+    let puzzle = Puzzle::new(
+        positional(&mut args, "year")?,
+        positional(&mut args, "day")?,
+    )?;
+    let dir = args
+        .value::<PathBuf>("--dir")?
+        .unwrap_or_else(|| puzzle.dir());
+    args.finish()?;
+    download(&Client::from_env()?, puzzle, &dir)
+}
+
+/// Writes the `README.md` and `input` of `puzzle` into `dir`.
+fn download(client: &Client, puzzle: Puzzle, dir: &Path) -> Result<()> {
+    let readme = client::readme(&client.page(puzzle)?);
+    let input = client.input(puzzle)?;
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    for (name, contents) in [("README.md", readme), ("input", input)] {
+        let path = dir.join(name);
+        fs::write(&path, contents).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn submit(mut args: Args) -> Result<()> {
+    let puzzle = Puzzle::new(
+        positional(&mut args, "year")?,
+        positional(&mut args, "day")?,
+    )?;
+    let part: Part = positional::<String>(&mut args, "part")?.parse()?;
+    let answer: String = positional(&mut args, "answer")?;
+    let readme = args.value::<PathBuf>("--readme")?;
+    args.finish()?;
+    let client = Client::from_env()?;
+    let reply = client.submit(puzzle, part, &answer)?;
+    if reply.verdict != Verdict::Correct {
+        bail!("{}", reply.message);
+    }
+    println!("{}", reply.message);
+    if let Some(path) = readme {
+        let readme = client::readme(&client.page(puzzle)?);
+        fs::write(&path, readme).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn serve(mut args: Args) -> Result<()> {
+    let fixtures: String = positional(&mut args, "fixture directory")?;
+    let port = args.value::<u16>("--port")?.unwrap_or(8080);
+    let cooldown = args.value::<u64>("--cooldown")?;
+    args.finish()?;
+    let mut site = Site::new(fixtures);
+    if let Some(cooldown) = cooldown {
+        site.cooldown = Duration::from_secs(cooldown);
+    }
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("listening on port {port}"))?;
+    eprintln!("serving on http://127.0.0.1:{port}");
+    site.serve(&listener)
+}
@@ -0,0 +1,357 @@
+//! A small cursor based parser for puzzle inputs.
+//!
+//! Every error carries the line and column it occurred at, so a malformed
+//! input points right at the offending character.
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// A one based line and column in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub position: Position,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.position, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+pub type Result<T, E = ParseError> = std::result::Result<T, E>;
+
+/// A position in the input that parsers advance.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    offset: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, offset: 0 }
+    }
+
+    /// The input not consumed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.offset..]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rest().is_empty()
+    }
+
+    pub fn position(&self) -> Position {
+        let before = &self.input[..self.offset];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// An error at the current position.
+    pub fn error(&self, message: impl Display) -> ParseError {
+        ParseError {
+            position: self.position(),
+            message: message.to_string(),
+        }
+    }
+
+    fn describe_next(&self) -> String {
+        match self.rest().chars().next() {
+            Some('\n') => "end of line".into(),
+            Some(c) => format!("{c:?}"),
+            None => "end of input".into(),
+        }
+    }
+
+    /// Consumes and returns the longest prefix matching `predicate`.
+    pub fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.offset += len;
+        &rest[..len]
+    }
+
+    /// Consumes `tag` if the input continues with it.
+    pub fn eat(&mut self, tag: &str) -> bool {
+        let matches = self.rest().starts_with(tag);
+        if matches {
+            self.offset += tag.len();
+        }
+        matches
+    }
+
+    /// Consumes `tag`, failing if the input does not continue with it.
+    pub fn tag(&mut self, tag: &str) -> Result<()> {
+        if self.eat(tag) {
+            Ok(())
+        } else {
+            Err(self.error(format_args!(
+                "expected {tag:?}, found {}",
+                self.describe_next()
+            )))
+        }
+    }
+
+    /// Consumes a non-empty token made of characters matching `predicate`
+    /// and converts it with [`FromStr`].
+    fn token<T>(&mut self, what: &str, predicate: impl Fn(char) -> bool) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let start = *self;
+        let token = self.take_while(predicate);
+        if token.is_empty() {
+            return Err(self.error(format_args!(
+                "expected {what}, found {}",
+                self.describe_next()
+            )));
+        }
+        token
+            .parse()
+            .map_err(|err| start.error(format_args!("invalid {what} {token:?}: {err}")))
+    }
+
+    /// A number without sign.
+    pub fn unsigned<T>(&mut self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.token("number", |c| c.is_ascii_digit())
+    }
+
+    /// A number optionally preceded by `-` or `+`.
+    pub fn signed<T>(&mut self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let start = *self;
+        let sign = self.take_while(|c| c == '-' || c == '+').len();
+        if sign > 1 {
+            return Err(start.error("expected number, found multiple signs"));
+        }
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(self.error(format_args!(
+                "expected number, found {}",
+                self.describe_next()
+            )));
+        }
+        let token = &start.rest()[..sign + digits.len()];
+        token
+            .parse()
+            .map_err(|err| start.error(format_args!("invalid number {token:?}: {err}")))
+    }
+
+    /// A non-empty run of characters other than whitespace.
+    pub fn word(&mut self) -> Result<&'a str> {
+        let word = self.take_while(|c| !c.is_whitespace());
+        if word.is_empty() {
+            Err(self.error(format_args!(
+                "expected word, found {}",
+                self.describe_next()
+            )))
+        } else {
+            Ok(word)
+        }
+    }
+
+    /// One or more `item`s separated by `separator`.
+    pub fn separated<T>(
+        &mut self,
+        separator: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut items = vec![item(self)?];
+        while self.eat(separator) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// A `label` followed by a value and the end of the line, e.g.
+    /// `Test: divisible by 23`.
+    pub fn field<T>(
+        &mut self,
+        label: &str,
+        value: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        self.tag(label)?;
+        let value = value(self)?;
+        self.line_end()?;
+        Ok(value)
+    }
+
+    /// Consumes a line break, also accepting the end of the input.
+    pub fn line_end(&mut self) -> Result<()> {
+        if self.eat("\r\n") || self.eat("\n") || self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format_args!(
+                "expected end of line, found {}",
+                self.describe_next()
+            )))
+        }
+    }
+
+    /// Parses every line with `line` until the end of the input or a blank
+    /// line, which is not consumed.
+    pub fn lines<T>(&mut self, mut line: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut lines = Vec::new();
+        while !self.is_empty() && !self.at_blank_line() {
+            lines.push(line(self)?);
+            self.line_end()?;
+        }
+        Ok(lines)
+    }
+
+    fn at_blank_line(&self) -> bool {
+        let rest = self.rest();
+        let line = &rest[..rest.find('\n').unwrap_or(rest.len())];
+        line.trim().is_empty()
+    }
+
+    /// Parses blocks separated by one or more blank lines with `block` until
+    /// the end of the input, failing if `block` consumes nothing.
+    pub fn blocks<T>(&mut self, mut block: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let mut blocks = Vec::new();
+        loop {
+            while !self.is_empty() && self.at_blank_line() {
+                self.take_while(|c| c != '\n');
+                self.line_end()?;
+            }
+            if self.is_empty() {
+                return Ok(blocks);
+            }
+            let offset = self.offset;
+            blocks.push(block(self)?);
+            if self.offset == offset {
+                return Err(self.error(format_args!(
+                    "expected a block, found {}",
+                    self.describe_next()
+                )));
+            }
+        }
+    }
+
+    /// Fails unless all input was consumed.
+    pub fn end(&self) -> Result<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format_args!(
+                "expected end of input, found {}",
+                self.describe_next()
+            )))
+        }
+    }
+}
+
+/// Runs `parser` on the whole `input`, failing if anything is left over.
+pub fn parse<'a, T>(
+    input: &'a str,
+    parser: impl FnOnce(&mut Cursor<'a>) -> Result<T>,
+) -> Result<T> {
+    let mut cursor = Cursor::new(input);
+    let value = parser(&mut cursor)?;
+    cursor.end()?;
+    Ok(value)
+}
+
+/// Runs `parser` on the whole `line`, the `number`th of the input, so that
+/// errors point into the input rather than the line.
+pub fn parse_line<'a, T>(
+    number: usize,
+    line: &'a str,
+    parser: impl FnOnce(&mut Cursor<'a>) -> Result<T>,
+) -> Result<T> {
+    parse(line, parser).map_err(|mut error| {
+        error.position.line = number;
+        error
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        let mut cursor = Cursor::new("12 -3 +4 x");
+        assert_eq!(cursor.unsigned::<u32>(), Ok(12));
+        cursor.tag(" ").unwrap();
+        assert_eq!(cursor.signed::<i32>(), Ok(-3));
+        cursor.tag(" ").unwrap();
+        assert_eq!(cursor.signed::<i32>(), Ok(4));
+        cursor.tag(" ").unwrap();
+        let error = cursor.unsigned::<u32>().unwrap_err();
+        assert_eq!(error.to_string(), "1:10: expected number, found 'x'");
+        assert_eq!(
+            Cursor::new("300").unsigned::<u8>().unwrap_err().to_string(),
+            "1:1: invalid number \"300\": number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn fields() {
+        let input = "Items: 1, 2, 3\nName: abc\n";
+        let (items, name) = parse(input, |cursor| {
+            let items = cursor.field("Items: ", |c| c.separated(", ", Cursor::unsigned::<u8>))?;
+            let name = cursor.field("Name: ", Cursor::word)?;
+            Ok((items, name))
+        })
+        .unwrap();
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(name, "abc");
+
+        let error = parse("Items: 1, 2,3\n", |cursor| {
+            cursor.field("Items: ", |c| c.separated(", ", Cursor::unsigned::<u8>))
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "1:12: expected end of line, found ','");
+    }
+
+    #[test]
+    fn blocks() {
+        let input = "1\n2\n\n\n3\n";
+        let blocks = parse(input, |cursor| {
+            cursor.blocks(|c| c.lines(Cursor::unsigned::<u32>))
+        })
+        .unwrap();
+        assert_eq!(blocks, [vec![1, 2], vec![3]]);
+
+        let error = parse("1\n\n2\nx\n", |cursor| {
+            cursor.blocks(|c| c.lines(Cursor::unsigned::<u32>))
+        })
+        .unwrap_err();
+        assert_eq!(error.position, Position { line: 4, column: 1 });
+
+        let error = parse("1\n\nx\n", |cursor| {
+            cursor.blocks(|c| Ok(c.take_while(|c| c.is_ascii_digit())))
+        })
+        .unwrap_err();
+        assert_eq!(error.to_string(), "3:1: expected a block, found 'x'");
+    }
+}
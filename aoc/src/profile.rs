@@ -0,0 +1,264 @@
+//! Timing the phases of a solution, like parsing and searching, with nested
+//! spans. Spans cost next to nothing unless the runner is profiling, with
+//! `--profile` for a breakdown on stderr or `--trace <path>` for a Chrome
+//! trace, which `chrome://tracing` and Perfetto open.
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Write as _,
+    time::{Duration, Instant},
+};
+
+/// A finished span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// The names of the enclosing spans and then this one.
+    pub path: Vec<&'static str>,
+    /// Since profiling started.
+    pub start: Duration,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+struct Profiler {
+    origin: Instant,
+    open: Vec<(&'static str, Instant)>,
+    records: Vec<Record>,
+}
+
+thread_local! {
+    static PROFILER: RefCell<Option<Profiler>> = const { RefCell::new(None) };
+}
+
+/// Starts recording the spans of this thread, dropping earlier ones.
+pub fn start() {
+    PROFILER.with_borrow_mut(|profiler| {
+        *profiler = Some(Profiler {
+            origin: Instant::now(),
+            open: Vec::new(),
+            records: Vec::new(),
+        });
+    });
+}
+
+/// Stops recording, returning the finished spans ordered by their start.
+pub fn finish() -> Vec<Record> {
+    let mut records = PROFILER
+        .with_borrow_mut(Option::take)
+        .map_or_else(Vec::new, |profiler| profiler.records);
+    records.sort_by_key(|record| (record.start, record.path.len()));
+    records
+}
+
+/// Ends when dropped. Spans have to end in the reverse order they started.
+#[must_use = "the span ends when it is dropped"]
+#[derive(Debug)]
+pub struct Span {
+    recording: bool,
+}
+
+/// Starts a span inside the innermost open one.
+pub fn span(name: &'static str) -> Span {
+    let recording = PROFILER.with_borrow_mut(|profiler| match profiler {
+        Some(profiler) => {
+            profiler.open.push((name, Instant::now()));
+            true
+        }
+        None => false,
+    });
+    Span { recording }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !self.recording {
+            return;
+        }
+        let end = Instant::now();
+        PROFILER.with_borrow_mut(|profiler| {
+            let Some(profiler) = profiler else {
+                return;
+            };
+            let path = profiler.open.iter().map(|&(name, _)| name).collect();
+            let Some((_, start)) = profiler.open.pop() else {
+                return;
+            };
+            profiler.records.push(Record {
+                path,
+                start: start - profiler.origin,
+                elapsed: end - start,
+            });
+        });
+    }
+}
+
+/// Runs `f` in a span.
+pub fn time<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let _span = span(name);
+    f()
+}
+
+/// Sums up the spans by their path, like a flame graph turned sideways, with
+/// the share of the total time of the outermost spans.
+pub fn report(records: &[Record]) -> String {
+    let mut totals: Vec<(&[&str], usize, Duration)> = Vec::new();
+    let mut index = HashMap::new();
+    for record in records {
+        let idx = *index.entry(record.path.as_slice()).or_insert_with(|| {
+            totals.push((record.path.as_slice(), 0, Duration::ZERO));
+            totals.len() - 1
+        });
+        totals[idx].1 += 1;
+        totals[idx].2 += record.elapsed;
+    }
+    let total: Duration = totals
+        .iter()
+        .filter(|(path, ..)| path.len() == 1)
+        .map(|&(.., elapsed)| elapsed)
+        .sum();
+    let labels: Vec<_> = totals
+        .iter()
+        .map(|&(path, calls, _)| {
+            let name = path.last().copied().unwrap_or_default();
+            let indent = "  ".repeat(path.len() - 1);
+            match calls {
+                1 => format!("{indent}{name}"),
+                calls => format!("{indent}{name} ×{calls}"),
+            }
+        })
+        .collect();
+    let width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or_default();
+    let mut out = String::new();
+    for (label, &(.., elapsed)) in labels.iter().zip(&totals) {
+        let share = if total.is_zero() {
+            0.0
+        } else {
+            elapsed.as_secs_f64() / total.as_secs_f64()
+        };
+        let bar = "█".repeat((share * 20.0).round() as usize);
+        let padding = width - label.chars().count();
+        let line = format!(
+            "{label}{:padding$}  {:>7}  {:>5.1}%  {bar}",
+            "",
+            format!("{elapsed:.1?}"),
+            share * 100.0,
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The spans in the Chrome trace event format, as complete events.
+pub fn chrome_trace(records: &[Record]) -> String {
+    let events: Vec<_> = records
+        .iter()
+        .map(|record| {
+            format!(
+                "{{\"name\":\"{}\",\"cat\":\"aoc\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":1,\"tid\":1}}",
+                escape(record.path.last().copied().unwrap_or_default()),
+                record.start.as_secs_f64() * 1e6,
+                record.elapsed.as_secs_f64() * 1e6,
+            )
+        })
+        .collect();
+    format!(
+        "{{\"traceEvents\":[\n{}\n],\"displayTimeUnit\":\"ms\"}}\n",
+        events.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn record(path: &[&'static str], start: u64, elapsed: u64) -> Record {
+        Record {
+            path: path.to_vec(),
+            start: Duration::from_micros(start),
+            elapsed: Duration::from_micros(elapsed),
+        }
+    }
+
+    #[test]
+    fn spans() {
+        let _ignored = span("before");
+        start();
+        {
+            let _solve = span("solve");
+            time("parse", || ());
+            for _ in 0..2 {
+                let _search = span("search");
+            }
+        }
+        let records = finish();
+        let paths: Vec<_> = records.iter().map(|record| record.path.join("/")).collect();
+        assert_eq!(
+            paths,
+            ["solve", "solve/parse", "solve/search", "solve/search"]
+        );
+        assert!(records[0].elapsed >= records[1].elapsed + records[2].elapsed);
+        let _after = span("after");
+        assert!(finish().is_empty());
+    }
+
+    #[test]
+    fn report() {
+        let records = [
+            record(&["part two"], 0, 10_000),
+            record(&["part two", "parse"], 0, 2_500),
+            record(&["part two", "tables"], 2_500, 5_000),
+            record(&["part two", "tables", "find"], 2_500, 1_000),
+            record(&["part two", "tables", "find"], 3_500, 1_500),
+            record(&["part two", "count"], 7_500, 2_500),
+        ];
+        assert_eq!(
+            super::report(&records),
+            indoc! {"
+                part two      10.0ms  100.0%  ████████████████████
+                  parse        2.5ms   25.0%  █████
+                  tables       5.0ms   50.0%  ██████████
+                    find ×2    2.5ms   25.0%  █████
+                  count        2.5ms   25.0%  █████
+            "}
+        );
+    }
+
+    #[test]
+    fn chrome_trace() {
+        let records = [
+            record(&["part \"one\""], 0, 1500),
+            record(&["part \"one\"", "parse"], 250, 500),
+        ];
+        assert_eq!(
+            super::chrome_trace(&records),
+            indoc! {r#"
+                {"traceEvents":[
+                {"name":"part \"one\"","cat":"aoc","ph":"X","ts":0.000,"dur":1500.000,"pid":1,"tid":1},
+                {"name":"parse","cat":"aoc","ph":"X","ts":250.000,"dur":500.000,"pid":1,"tid":1}
+                ],"displayTimeUnit":"ms"}
+            "#}
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! The puzzle description in each day's `README.md`, as downloaded with
+//! `aoc r` into markdown.
+use std::str::FromStr;
+
+use anyhow::{bail, ensure, Context, Error, Result};
+
+use crate::runner::Part;
+
+/// Lines starting with these belong to the page around the puzzle, not to
+/// the description of a part.
+const FOOTERS: [&str; 8] = [
+    "Your puzzle answer was",
+    "Both parts of this puzzle are complete!",
+    "The first half of this puzzle is complete!",
+    "At this point, you",
+    "If you still want to see it,",
+    "To begin, [get your puzzle input]",
+    "Answer:",
+    "You can also [Share",
+];
+
+/// The description and, once solved, the answer of one part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Description {
+    /// The markdown of the description.
+    pub text: String,
+    /// The contents of every fenced code block, usually the examples.
+    pub code_blocks: Vec<String>,
+    /// Emphasized code like `` `*24000*` ``, which is how the description
+    /// marks the answers for its examples.
+    pub emphasized: Vec<String>,
+    /// The answer given in "Your puzzle answer was", if the part is solved.
+    pub answer: Option<String>,
+}
+
+impl Description {
+    fn parse(lines: &[&str]) -> Self {
+        let mut description = Self::default();
+        let mut text = Vec::new();
+        let mut code_block: Option<String> = None;
+        for &line in lines {
+            if line.starts_with("```") {
+                match code_block.take() {
+                    Some(block) => {
+                        let block = block.trim_end_matches('\n');
+                        description.code_blocks.push(format!("{block}\n"));
+                    }
+                    None => code_block = Some(String::new()),
+                }
+                text.push(line);
+                continue;
+            }
+            if let Some(block) = &mut code_block {
+                block.push_str(line);
+                block.push('\n');
+                text.push(line);
+                continue;
+            }
+            if let Some(answer) = line.strip_prefix("Your puzzle answer was ") {
+                description.answer =
+                    Some(answer.trim_end_matches('.').trim_matches('`').to_string());
+            }
+            if FOOTERS.iter().any(|footer| line.starts_with(footer)) {
+                continue;
+            }
+            description.emphasized.extend(emphasized(line));
+            text.push(line);
+        }
+        description.text = text.join("\n").trim().to_string();
+        description
+    }
+}
+
+/// The contents of `` `*x*` `` and ``*`x`*`` in `line`.
+fn emphasized(line: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("`*").into_iter().chain(rest.find("*`")).min() {
+        let (open, close) = if rest[start..].starts_with("`*") {
+            ("`*", "*`")
+        } else {
+            ("*`", "`*")
+        };
+        let inner = &rest[start + open.len()..];
+        match inner.find(close) {
+            Some(end) if end > 0 => {
+                found.push(inner[..end].to_string());
+                rest = &inner[end + close.len()..];
+            }
+            _ => rest = inner,
+        }
+    }
+    found
+}
+
+/// A puzzle page, one or two parts depending on how far it was solved when
+/// it was downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Readme {
+    pub day: u8,
+    /// Taken from the share and calendar links, which every downloaded page
+    /// has.
+    pub year: Option<u16>,
+    pub title: String,
+    pub parts: Vec<Description>,
+}
+
+impl Readme {
+    pub fn part(&self, part: Part) -> Option<&Description> {
+        self.parts.get(match part {
+            Part::One => 0,
+            Part::Two => 1,
+        })
+    }
+
+    /// The number of solved parts.
+    pub fn stars(&self) -> usize {
+        self.parts
+            .iter()
+            .filter(|part| part.answer.is_some())
+            .count()
+    }
+
+    /// The first code block, which is the example input on most days.
+    pub fn example(&self) -> Option<&str> {
+        self.parts.first()?.code_blocks.first().map(String::as_str)
+    }
+}
+
+/// Parses `\--- Day 1: Calorie Counting ---` into the day and title.
+fn header(line: &str) -> Result<(u8, String)> {
+    let inner = line
+        .trim_start_matches('\\')
+        .strip_prefix("--- Day ")
+        .and_then(|line| line.strip_suffix(" ---"))
+        .context("expected a `--- Day <day>: <title> ---` header")?;
+    let (day, title) = inner
+        .split_once(": ")
+        .context("expected a `:` between day and title")?;
+    let day = day
+        .parse()
+        .with_context(|| format!("invalid day {day:?}"))?;
+    Ok((day, title.to_string()))
+}
+
+/// Finds the year in the share link, like
+/// `text=...+Advent+of+Code+2022&url=...`, or in the calendar link
+/// `[return to your Advent calendar](/2022)`.
+///
+/// Links in the description may point to puzzles of other years.
+fn year(text: &str) -> Option<u16> {
+    [
+        "Advent+of+Code+",
+        "adventofcode%2Ecom%2F",
+        "Advent calendar](/",
+    ]
+    .iter()
+    .flat_map(|prefix| {
+        text.match_indices(prefix)
+            .map(|(idx, _)| idx + prefix.len())
+    })
+    .filter_map(|start| text.get(start..start + 4))
+    .find_map(|year| year.parse().ok())
+}
+
+impl FromStr for Readme {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<_> = s.lines().collect();
+        let Some(first) = lines.first() else {
+            bail!("README is empty");
+        };
+        let (day, title) = header(first).context("line 1")?;
+        let mut starts = vec![1];
+        starts.extend(
+            lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.trim_start_matches('\\') == "--- Part Two ---")
+                .map(|(idx, _)| idx + 1),
+        );
+        ensure!(starts.len() <= 2, "README has more than two parts");
+        let parts = starts
+            .iter()
+            .zip(
+                starts
+                    .iter()
+                    .skip(1)
+                    .map(|&end| end - 1)
+                    .chain([lines.len()]),
+            )
+            .map(|(&start, end)| {
+                // Skip the line underlining the heading.
+                let start =
+                    start + usize::from(lines.get(start).is_some_and(|l| l.starts_with("---")));
+                Description::parse(&lines[start..end])
+            })
+            .collect();
+        Ok(Self {
+            day,
+            year: year(s),
+            title,
+            parts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path};
+
+    use super::*;
+
+    const README: &str = "\\--- Day 1: Calorie Counting ---
+----------
+
+For example:
+
+```
+1000
+2000
+
+```
+
+That is `*3000*`, and *`2000`* is *not* it.
+
+Your puzzle answer was `72718`.
+
+\\--- Part Two ---
+----------
+
+Now the top `*3*`.
+
+Answer:
+
+You can also [Shareon [Twitter](https://twitter.com/intent/tweet?url=https%3A%2F%2Fadventofcode%2Ecom%2F2022%2Fday%2F1) this puzzle.
+";
+
+    #[test]
+    fn parse() {
+        let readme: Readme = README.parse().unwrap();
+        assert_eq!(readme.day, 1);
+        assert_eq!(readme.year, Some(2022));
+        assert_eq!(readme.title, "Calorie Counting");
+        assert_eq!(readme.stars(), 1);
+        assert_eq!(readme.example(), Some("1000\n2000\n"));
+
+        let one = readme.part(Part::One).unwrap();
+        assert_eq!(one.answer.as_deref(), Some("72718"));
+        assert_eq!(one.emphasized, ["3000", "2000"]);
+        assert!(one.text.starts_with("For example:"));
+        assert!(one.text.ends_with("is *not* it."));
+
+        let two = readme.part(Part::Two).unwrap();
+        assert_eq!(two.text, "Now the top `*3*`.");
+        assert_eq!(two.answer, None);
+        assert_eq!(two.emphasized, ["3"]);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!("".parse::<Readme>().is_err());
+        let error = "# Day 1\n".parse::<Readme>().unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "line 1: expected a `--- Day <day>: <title> ---` header"
+        );
+        assert!("\\--- Day x: Title ---\n".parse::<Readme>().is_err());
+    }
+
+    /// Every README downloaded into the repository parses and matches its
+    /// directory.
+    #[test]
+    fn downloaded() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut parsed = 0;
+        for year in fs::read_dir(&root).unwrap().flatten() {
+            let Ok(year_number) = year.file_name().to_string_lossy().parse::<u16>() else {
+                continue;
+            };
+            for day in fs::read_dir(year.path()).unwrap().flatten() {
+                let Ok(text) = fs::read_to_string(day.path().join("README.md")) else {
+                    continue;
+                };
+                let readme: Readme = text.parse().unwrap();
+                let day_number: u8 = day.file_name().to_string_lossy().parse().unwrap();
+                assert_eq!((readme.year, readme.day), (Some(year_number), day_number));
+                assert!(readme.example().is_some());
+                assert!(!readme.parts[0].emphasized.is_empty());
+                parsed += 1;
+            }
+        }
+        assert!(parsed > 0);
+    }
+}
@@ -0,0 +1,248 @@
+//! When puzzles unlock, and waiting for that: every puzzle is released at
+//! midnight EST, which is 05:00 UTC, on its day of December.
+use std::{
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+
+use crate::template::Puzzle;
+
+/// Where the time comes from, so that waiting can be tested without waiting.
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// The moment `puzzle` unlocks.
+pub fn release(puzzle: Puzzle) -> SystemTime {
+    let days = days_from_civil(puzzle.year.into(), 12, puzzle.day.into());
+    let seconds = days * 24 * 60 * 60 + 5 * 60 * 60;
+    // Puzzles start in 2015, long after the epoch.
+    UNIX_EPOCH + Duration::from_secs(seconds.unsigned_abs())
+}
+
+/// The first puzzle released after `now`.
+pub fn next(now: SystemTime) -> Puzzle {
+    let seconds = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    // Starting a year early is simpler than finding the exact date.
+    let year = (1970 + seconds / 31_556_952).saturating_sub(1).max(2015);
+    (year..)
+        .filter_map(|year| u16::try_from(year).ok())
+        .flat_map(|year| (1..=25).map(move |day| Puzzle { year, day }))
+        .find(|&puzzle| release(puzzle) > now)
+        .expect("puzzles are released every year")
+}
+
+/// Formats the time left like `1d 02:03:04`.
+pub fn countdown(left: Duration) -> String {
+    let seconds = left.as_secs() + u64::from(left.subsec_nanos() > 0);
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+    match days {
+        0 => clock,
+        days => format!("{days}d {clock}"),
+    }
+}
+
+/// Sleeps until `puzzle` unlocks, calling `on_tick` with the time left about
+/// once a second.
+pub fn wait(clock: &impl Clock, puzzle: Puzzle, mut on_tick: impl FnMut(Duration)) {
+    let release = release(puzzle);
+    while let Some(left) = release
+        .duration_since(clock.now())
+        .ok()
+        .filter(|left| !left.is_zero())
+    {
+        on_tick(left);
+        let fraction = Duration::from_nanos(left.subsec_nanos().into());
+        clock.sleep(if fraction.is_zero() {
+            Duration::from_secs(1)
+        } else {
+            fraction
+        });
+    }
+}
+
+/// How often and how patiently [`retry`] tries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    pub attempts: u32,
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for Backoff {
+    /// Enough for the minute or so in which a new puzzle may still be
+    /// missing right after its release.
+    fn default() -> Self {
+        Self {
+            attempts: 8,
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Calls `attempt` until it succeeds, doubling the delay between attempts.
+pub fn retry<T>(
+    clock: &impl Clock,
+    backoff: Backoff,
+    mut attempt: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut delay = backoff.initial;
+    for _ in 1..backoff.attempts {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(error) => {
+                eprintln!("{error:#}, retrying in {delay:?}");
+                clock.sleep(delay);
+                delay = (delay * 2).min(backoff.max);
+            }
+        }
+    }
+    attempt().with_context(|| format!("giving up after {} attempts", backoff.attempts))
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::{Cell, RefCell};
+
+    use anyhow::bail;
+
+    use super::*;
+
+    /// A clock whose time only passes when sleeping.
+    struct FakeClock {
+        now: Cell<SystemTime>,
+        sleeps: RefCell<Vec<Duration>>,
+    }
+
+    impl FakeClock {
+        fn at(now: SystemTime) -> Self {
+            Self {
+                now: Cell::new(now),
+                sleeps: RefCell::default(),
+            }
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> SystemTime {
+            self.now.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.now.set(self.now.get() + duration);
+            self.sleeps.borrow_mut().push(duration);
+        }
+    }
+
+    #[test]
+    fn release() {
+        let puzzle = Puzzle::new(2022, 1).unwrap();
+        // 2022-12-01T05:00:00Z
+        assert_eq!(
+            super::release(puzzle),
+            UNIX_EPOCH + Duration::from_secs(1_669_870_800)
+        );
+        let puzzle = Puzzle::new(2020, 25).unwrap();
+        // 2020-12-25T05:00:00Z
+        assert_eq!(
+            super::release(puzzle),
+            UNIX_EPOCH + Duration::from_secs(1_608_872_400)
+        );
+    }
+
+    #[test]
+    fn next() {
+        let day = |year, day| Puzzle::new(year, day).unwrap();
+        let release = super::release(day(2022, 7));
+        assert_eq!(super::next(release - Duration::from_secs(1)), day(2022, 7));
+        assert_eq!(super::next(release), day(2022, 8));
+        let summer = UNIX_EPOCH + Duration::from_secs(1_656_633_600);
+        assert_eq!(super::next(summer), day(2022, 1));
+        let christmas = super::release(day(2022, 25));
+        assert_eq!(super::next(christmas), day(2023, 1));
+    }
+
+    #[test]
+    fn countdown() {
+        assert_eq!(super::countdown(Duration::from_millis(59_500)), "00:01:00");
+        assert_eq!(super::countdown(Duration::from_secs(93_784)), "1d 02:03:04");
+    }
+
+    #[test]
+    fn wait() {
+        let puzzle = Puzzle::new(2022, 7).unwrap();
+        let clock = FakeClock::at(super::release(puzzle) - Duration::from_millis(2500));
+        let mut ticks = Vec::new();
+        super::wait(&clock, puzzle, |left| ticks.push(super::countdown(left)));
+        assert_eq!(clock.now(), super::release(puzzle));
+        assert_eq!(ticks, ["00:00:03", "00:00:02", "00:00:01"]);
+        assert_eq!(
+            *clock.sleeps.borrow(),
+            [500, 1000, 1000].map(Duration::from_millis)
+        );
+
+        let clock = FakeClock::at(super::release(puzzle) + Duration::from_secs(1));
+        super::wait(&clock, puzzle, |_| panic!("the puzzle is out already"));
+        assert!(clock.sleeps.borrow().is_empty());
+    }
+
+    #[test]
+    fn retry() {
+        let clock = FakeClock::at(UNIX_EPOCH);
+        let backoff = Backoff {
+            attempts: 5,
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(3),
+        };
+        let mut failures = 3;
+        let value = super::retry(&clock, backoff, || {
+            if failures > 0 {
+                failures -= 1;
+                bail!("not found");
+            }
+            Ok(42)
+        });
+        assert_eq!(value.unwrap(), 42);
+        assert_eq!(*clock.sleeps.borrow(), [1, 2, 3].map(Duration::from_secs));
+
+        let error = super::retry(&clock, backoff, || -> Result<()> { bail!("not found") });
+        assert_eq!(
+            format!("{:#}", error.err().unwrap()),
+            "giving up after 5 attempts: not found"
+        );
+    }
+}
@@ -0,0 +1,360 @@
+//! The command line every day's binary shares.
+//!
+//! `cargo run [1|2] [--check-input] [--verbose] [--profile] [--trace <path>]
+//! [day specific flags...]`
+//!
+//! `--verbose` shows the debug messages of the [`log`](crate::log), which are
+//! otherwise hidden unless enabled with `AOC_LOG`.
+//!
+//! `--profile` breaks the time of the solve down by the spans of the
+//! [`profile`](crate::profile) on stderr, and `--trace` writes them as a
+//! Chrome trace.
+//!
+//! Days with an input [`Generator`] additionally support
+//! `--generate <size> [--seed <seed>]`, printing a generated input, and
+//! `--stress <size> [--seed <first seed>] [--count <inputs>]`, solving many
+//! generated inputs.
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Instant,
+};
+
+use anyhow::{bail, Context, Error, Result};
+
+use crate::{
+    answer::Answer,
+    args::Args,
+    check::{self, Problem},
+    generate::{self, Generator},
+    log::{self, Filter, Level},
+    profile,
+    status::{self, Record},
+};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    #[default]
+    One,
+    Two,
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "1" => Self::One,
+            "2" => Self::Two,
+            other => bail!("{other} is not a valid part"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Runner {
+    pub part: Part,
+    /// Only report problems with the input instead of solving.
+    pub check_input: bool,
+    /// Whether trailing whitespace may be stripped when checking the input.
+    pub trim: bool,
+    /// Print how long the phases of the solve took.
+    pub profile: bool,
+    /// Where to write the phases as a Chrome trace.
+    pub trace: Option<PathBuf>,
+    /// Whether the answer is the puzzle's, so that its runtime is recorded
+    /// and it is compared with the recorded answer.
+    pub puzzle: bool,
+    generator: Option<Generator>,
+    args: Args,
+}
+
+impl Runner {
+    /// Reads the part and the shared flags from the command line, keeping the
+    /// remaining arguments for [`flag`](Self::flag) and
+    /// [`value`](Self::value).
+    pub fn from_env() -> Result<Self> {
+        Self::from_args(Args::from_env())
+    }
+
+    pub fn from_args(mut args: Args) -> Result<Self> {
+        let part = args
+            .positional()
+            .as_deref()
+            .map(Part::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        let mut runner = Self {
+            part,
+            trim: true,
+            puzzle: true,
+            args,
+            ..Self::default()
+        };
+        runner.check_input = runner.flag("--check-input");
+        runner.profile = runner.flag("--profile");
+        runner.trace = runner.value("--trace")?;
+        let mut filter = match env::var("AOC_LOG") {
+            Ok(spec) => spec.parse::<Filter>().context("invalid AOC_LOG")?,
+            Err(_) => Filter::default(),
+        };
+        if runner.flag("--verbose") {
+            filter.default = filter.default.max(Level::Debug);
+        }
+        log::set_filter(filter);
+        Ok(runner)
+    }
+
+    /// Keeps trailing whitespace when checking the input, for days where it
+    /// is significant.
+    pub fn keep_trailing_whitespace(mut self) -> Self {
+        self.trim = false;
+        self
+    }
+
+    /// Marks the answer as not the puzzle's, like when day specific flags
+    /// change the question.
+    pub fn custom(mut self) -> Self {
+        self.puzzle = false;
+        self
+    }
+
+    /// Enables `--generate` and `--stress` with inputs from `generator`.
+    pub fn generator(mut self, generator: Generator) -> Self {
+        self.generator = Some(generator);
+        self
+    }
+
+    /// Whether `name` was passed, consuming it.
+    pub fn flag(&mut self, name: &str) -> bool {
+        self.args.flag(name)
+    }
+
+    /// The value following `name`, consuming both.
+    pub fn value<T>(&mut self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        self.args.value(name)
+    }
+
+    /// Fails if any argument was not consumed.
+    pub fn finish(&self) -> Result<()> {
+        self.args.finish()
+    }
+
+    /// Solves the selected part, or only checks the input with `validate`
+    /// if `--check-input` was passed.
+    ///
+    /// Unless the runner is [`custom`](Self::custom), the answer is compared
+    /// with the one recorded for the day, if any, and the runtime of the
+    /// solve is recorded for `aoc status`. Checking, generating and stress
+    /// testing record nothing.
+    pub fn run<A: Into<Answer>, B: Into<Answer>>(
+        mut self,
+        input: &str,
+        validate: impl Fn(&str) -> Vec<Problem>,
+        one: impl Fn(&str) -> Result<A>,
+        two: impl Fn(&str) -> Result<B>,
+    ) -> Result<()> {
+        let seed = self.value("--seed")?;
+        if let Some(size) = self.value("--generate")? {
+            self.finish()?;
+            print!(
+                "{}",
+                generate::generate(self.input_generator()?, seed.unwrap_or(0), size)
+            );
+            return Ok(());
+        }
+        if let Some(size) = self.value("--stress")? {
+            let count = self.value("--count")?.unwrap_or(100);
+            self.finish()?;
+            let seed = seed.unwrap_or(0);
+            eprintln!("=== Stress Testing ===");
+            let stress = generate::stress(
+                self.input_generator()?,
+                validate,
+                one,
+                two,
+                seed..seed + count,
+                size,
+            );
+            for failure in &stress.failures {
+                eprintln!("error: {failure}");
+            }
+            eprintln!("{stress}");
+            if !stress.failures.is_empty() {
+                bail!(
+                    "{} of {} inputs failed",
+                    stress.failures.len(),
+                    stress.inputs
+                );
+            }
+            return Ok(());
+        }
+        self.finish()?;
+        if self.check_input {
+            return self.check(input, validate);
+        }
+
+        let part = self.part;
+        eprintln!("=== Solving Part {part:?} ===");
+
+        let profiling = self.profile || self.trace.is_some();
+        if profiling {
+            profile::start();
+        }
+        let start = Instant::now();
+        let answer: Answer = match part {
+            Part::One => profile::time("part one", || one(input))?.into(),
+            Part::Two => profile::time("part two", || two(input))?.into(),
+        };
+        let elapsed = start.elapsed();
+        if profiling {
+            let records = profile::finish();
+            if self.profile {
+                eprint!("{}", profile::report(&records));
+            }
+            if let Some(path) = &self.trace {
+                fs::write(path, profile::chrome_trace(&records))
+                    .with_context(|| format!("failed to write the trace to {}", path.display()))?;
+            }
+        }
+        println!("{}", answer.to_string().trim_end_matches('\n'));
+        if answer.submission().is_none() {
+            crate::warn!("the answer has to be read off before submitting it");
+        }
+        if self.puzzle {
+            // Spans slow the solve down a little, so do not keep those times.
+            if !profiling {
+                record_runtime(part, elapsed);
+            }
+            compare_recorded(part, &answer);
+        }
+
+        Ok(())
+    }
+
+    fn input_generator(&self) -> Result<Generator> {
+        self.generator
+            .context("there is no input generator for this day")
+    }
+
+    /// Reports everything [`check::normalize`] fixes and every problem
+    /// `validate` finds in the normalized input.
+    pub fn check(&self, input: &str, validate: impl Fn(&str) -> Vec<Problem>) -> Result<()> {
+        eprintln!("=== Checking Input ===");
+        let (input, fixes) = check::normalize(input, self.trim);
+        for fix in &fixes {
+            eprintln!("fixed: {fix}");
+        }
+        let problems = validate(&input);
+        for problem in &problems {
+            eprintln!("error: {problem}");
+        }
+        match problems.len() {
+            0 => {
+                eprintln!("input is valid");
+                Ok(())
+            }
+            1 => bail!("input has 1 problem"),
+            count => bail!("input has {count} problems"),
+        }
+    }
+}
+
+/// Records the runtime for `aoc status` when run through `cargo run`, which
+/// points to the day's crate with `CARGO_MANIFEST_DIR`.
+fn record_runtime(part: Part, elapsed: std::time::Duration) {
+    let Some(dir) = env::var_os("CARGO_MANIFEST_DIR") else {
+        return;
+    };
+    let recorded = Record::update(Path::new(&dir), |record| record.set_runtime(part, elapsed));
+    if let Err(error) = recorded {
+        crate::warn!("could not record the runtime: {error:#}");
+    }
+}
+
+/// Warns if `answer` differs from the answer recorded for the day in its
+/// `README.md` or ledger.
+fn compare_recorded(part: Part, answer: &Answer) {
+    let Some(dir) = env::var_os("CARGO_MANIFEST_DIR") else {
+        return;
+    };
+    match status::answers(Path::new(&dir)) {
+        Ok(answers) => {
+            if let Some(recorded) = &answers[part as usize] {
+                if answer.matches(recorded) == Some(false) {
+                    crate::warn!("the recorded answer is {recorded}");
+                }
+            }
+        }
+        Err(error) => crate::warn!("could not read the recorded answers: {error:#}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Runner> {
+        Runner::from_args(Args::new(args.iter().map(ToString::to_string)))
+    }
+
+    #[test]
+    fn arguments() {
+        let runner = args(&[]).unwrap();
+        assert_eq!(runner.part, Part::One);
+        assert!(!runner.check_input);
+        assert!(!runner.profile);
+        assert_eq!(runner.trace, None);
+        assert!(runner.puzzle);
+        assert!(!runner.custom().puzzle);
+
+        let mut runner = args(&[
+            "2",
+            "--step",
+            "--window",
+            "5",
+            "--check-input",
+            "--profile",
+            "--trace",
+            "trace.json",
+        ])
+        .unwrap();
+        assert_eq!(runner.part, Part::Two);
+        assert!(runner.check_input);
+        assert!(runner.profile);
+        assert_eq!(runner.trace, Some(PathBuf::from("trace.json")));
+        assert!(runner.flag("--step"));
+        assert!(!runner.flag("--all"));
+        assert_eq!(runner.value::<usize>("--window").unwrap(), Some(5));
+        runner.finish().unwrap();
+
+        assert!(args(&["3"]).is_err());
+        assert!(args(&["--unknown"]).unwrap().finish().is_err());
+    }
+
+    #[test]
+    fn check() {
+        let runner = args(&["--check-input"]).unwrap();
+        let validate = |input: &str| check::lines(input, |_| Ok(()));
+        assert!(runner.check("a\r\nb", validate).is_ok());
+        let validate = |input: &str| vec![Problem::new(1, format_args!("{input:?}"))];
+        assert!(runner.check("a", validate).is_err());
+    }
+
+    #[test]
+    fn generator() {
+        let validate = |_: &str| Vec::new();
+        let solve = |input: &str| -> Result<usize> { Ok(input.len()) };
+        let runner = args(&["--generate", "3"]).unwrap();
+        assert!(runner.run("", validate, solve, solve).is_err());
+        let runner = args(&["--stress", "3", "--count", "2"])
+            .unwrap()
+            .generator(|_, size| "x\n".repeat(size));
+        runner.run("", validate, solve, solve).unwrap();
+    }
+}
@@ -0,0 +1,552 @@
+//! A stand-in for adventofcode.com, serving puzzle pages, inputs and answer
+//! replies from a fixture directory, so fetching and submitting can be
+//! tested offline.
+//!
+//! The fixtures of a day live in `<fixtures>/<year>/<day>/`:
+//!
+//! - `puzzle.html` is the page before solving, `puzzle-1.html` and
+//!   `puzzle-2.html` the pages after one and two stars, falling back to the
+//!   previous one when missing.
+//! - `input` is the puzzle input.
+//! - `answers` is the ledger of correct answers, as read by
+//!   [`status::ledger`](crate::status::ledger).
+//!
+//! Puzzle pages carry an `ETag` and are answered with `304 Not Modified` when
+//! it matches `If-None-Match`.
+//!
+//! Like the real site, inputs and answers need a `session` cookie, a wrong
+//! answer makes that session wait before answering again, and answering a
+//! part that is solved already or not unlocked yet is the wrong level.
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    io::{BufRead, BufReader, ErrorKind, Read, Take, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, ensure, Context, Result};
+
+use crate::status;
+
+/// The largest request body read, far more than any answer needs.
+const MAX_BODY: usize = 64 * 1024;
+/// The largest request line and headers read, together.
+const MAX_HEAD: u64 = 8 * 1024;
+/// How long a connection may stall while sending its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A parsed HTTP request, with the header names in lowercase.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The value of the cookie `name`.
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.header("cookie")?
+            .split(';')
+            .filter_map(|cookie| cookie.trim().split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// The value of the field `name` in a form encoded body.
+    pub fn form(&self, name: &str) -> Option<String> {
+        self.body
+            .split('&')
+            .filter_map(|field| field.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| decode(value))
+    }
+
+    /// Reads a request from `reader`, or `None` if the connection was closed
+    /// before sending one. Heads over 8 KiB and bodies over 64 KiB are
+    /// refused.
+    pub fn read(reader: &mut impl BufRead) -> Result<Option<Self>> {
+        let mut head = reader.take(MAX_HEAD);
+        let mut line = String::new();
+        if head_line(&mut head, &mut line)? == 0 {
+            return Ok(None);
+        }
+        let mut words = line.split_whitespace();
+        let (Some(method), Some(path)) = (words.next(), words.next()) else {
+            bail!("invalid request line {line:?}");
+        };
+        let mut request = Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            ..Self::default()
+        };
+        loop {
+            let mut line = String::new();
+            head_line(&mut head, &mut line)?;
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .with_context(|| format!("invalid header {line:?}"))?;
+            request
+                .headers
+                .push((key.to_ascii_lowercase(), value.trim().to_string()));
+        }
+        let length = match request.header("content-length") {
+            Some(length) => length.parse().context("invalid content length")?,
+            None => 0,
+        };
+        ensure!(
+            length <= MAX_BODY,
+            "content length {length} is over the limit of {MAX_BODY} bytes"
+        );
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        request.body = String::from_utf8(body).context("body is not UTF-8")?;
+        Ok(Some(request))
+    }
+}
+
+/// Reads a line of the request head into `line`, failing if the head does not
+/// end within its limit.
+fn head_line(head: &mut Take<impl BufRead>, line: &mut String) -> Result<usize> {
+    let read = head.read_line(line)?;
+    ensure!(
+        head.limit() > 0 || line.ends_with('\n'),
+        "request head is over the limit of {MAX_HEAD} bytes"
+    );
+    Ok(read)
+}
+
+/// Decodes `+` and `%XX` escapes in a form value.
+fn decode(value: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        match byte {
+            b'+' => bytes.push(b' '),
+            b'%' => match rest
+                .get(..2)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(decoded) => {
+                    bytes.push(decoded);
+                    rest = &rest[2..];
+                }
+                None => bytes.push(b'%'),
+            },
+            _ => bytes.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub etag: Option<String>,
+    pub body: String,
+}
+
+impl Response {
+    fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type: "text/plain",
+            etag: None,
+            body: body.into(),
+        }
+    }
+
+    fn html(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html",
+            etag: None,
+            body: body.into(),
+        }
+    }
+
+    /// An answer reply, wrapped like the site wraps them.
+    fn article(message: &str) -> Self {
+        Self::html(format!(
+            "<!DOCTYPE html>\n<html><body><main>\n<article><p>{message}</p></article>\n</main></body></html>\n"
+        ))
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            304 => "Not Modified",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        }
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n",
+            self.status,
+            self.reason(),
+            self.content_type,
+            self.body.len(),
+        )?;
+        if let Some(etag) = &self.etag {
+            write!(writer, "ETag: {etag}\r\n")?;
+        }
+        write!(writer, "Connection: close\r\n\r\n{}", self.body)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+const NOT_UNLOCKED: &str = "Please don't repeatedly request this endpoint before it unlocks! The calendar countdown is synchronized with the server time; the link will be enabled on the calendar the instant this puzzle becomes available.\n";
+const LOG_IN: &str = "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+
+/// The fixtures and what every session solved and answered so far.
+#[derive(Debug, Clone)]
+pub struct Site {
+    fixtures: PathBuf,
+    /// How long a session has to wait after a wrong answer.
+    pub cooldown: Duration,
+    stars: HashMap<(String, u16, u8), u8>,
+    wrong: HashMap<String, Instant>,
+}
+
+impl Site {
+    pub fn new(fixtures: impl Into<PathBuf>) -> Self {
+        Self {
+            fixtures: fixtures.into(),
+            cooldown: Duration::from_secs(60),
+            stars: HashMap::new(),
+            wrong: HashMap::new(),
+        }
+    }
+
+    fn dir(&self, year: u16, day: u8) -> PathBuf {
+        self.fixtures.join(year.to_string()).join(day.to_string())
+    }
+
+    /// Answers `request` as the site would at `now`.
+    pub fn respond(&mut self, request: &Request, now: Instant) -> Response {
+        match self.route(request, now) {
+            Ok(response) => response,
+            Err(error) => Response::text(500, format!("{error:#}\n")),
+        }
+    }
+
+    fn route(&mut self, request: &Request, now: Instant) -> Result<Response> {
+        let path = request.path.split('?').next().unwrap_or_default();
+        let segments: Vec<_> = path.trim_matches('/').split('/').collect();
+        let (year, day, rest) = match segments[..] {
+            [year, "day", day, ref rest @ ..] => match (year.parse(), day.parse()) {
+                (Ok(year), Ok(day)) => (year, day, rest),
+                _ => return Ok(Response::text(404, "404 Not Found\n")),
+            },
+            _ => return Ok(Response::text(404, "404 Not Found\n")),
+        };
+        let dir = self.dir(year, day);
+        if !dir.join("puzzle.html").exists() {
+            return Ok(Response::text(404, NOT_UNLOCKED));
+        }
+        let session = request.cookie("session").map(str::to_string);
+        match (request.method.as_str(), rest) {
+            ("GET", []) => {
+                let stars = match &session {
+                    Some(session) => self.stars(session, year, day),
+                    None => 0,
+                };
+                let page = page(&dir, stars)?;
+                let mut hasher = DefaultHasher::new();
+                page.hash(&mut hasher);
+                let etag = format!("\"{:016x}\"", hasher.finish());
+                let mut response = Response::html(page);
+                if request.header("if-none-match") == Some(etag.as_str()) {
+                    response.status = 304;
+                    response.body.clear();
+                }
+                response.etag = Some(etag);
+                Ok(response)
+            }
+            ("GET", ["input"]) => match session {
+                Some(_) => Ok(Response::text(200, read(&dir.join("input"))?)),
+                None => Ok(Response::text(400, LOG_IN)),
+            },
+            ("POST", ["answer"]) => match session {
+                Some(session) => self.answer(request, &session, year, day, now),
+                None => Ok(Response::text(400, LOG_IN)),
+            },
+            (_, [] | ["input"] | ["answer"]) => Ok(Response::text(405, "405 Method Not Allowed\n")),
+            _ => Ok(Response::text(404, "404 Not Found\n")),
+        }
+    }
+
+    fn stars(&self, session: &str, year: u16, day: u8) -> u8 {
+        let key = (session.to_string(), year, day);
+        self.stars.get(&key).copied().unwrap_or_default()
+    }
+
+    fn answer(
+        &mut self,
+        request: &Request,
+        session: &str,
+        year: u16,
+        day: u8,
+        now: Instant,
+    ) -> Result<Response> {
+        let back = format!("[<a href=\"/{year}/day/{day}\">Return to Day {day}</a>]");
+        let (Some(level), Some(answer)) = (request.form("level"), request.form("answer")) else {
+            return Ok(Response::text(400, "expected a level and an answer\n"));
+        };
+        let stars = self.stars(session, year, day);
+        if level != (stars + 1).to_string() || stars == 2 {
+            return Ok(Response::article(&format!(
+                "You don't seem to be solving the right level.  Did you already complete it? {back}"
+            )));
+        }
+        if let Some(left) = self
+            .wrong
+            .get(session)
+            .and_then(|&wrong| (wrong + self.cooldown).checked_duration_since(now))
+            .filter(|left| !left.is_zero())
+        {
+            return Ok(Response::article(&format!(
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have {}s left to wait. {back}",
+                left.as_secs_f64().ceil()
+            )));
+        }
+        let answers = status::ledger(&self.dir(year, day))?;
+        let Some(correct) = &answers[usize::from(stars)] else {
+            bail!("no answer for level {level} of {year} day {day} in the fixtures");
+        };
+        let answer = answer.trim();
+        if answer == correct {
+            self.stars
+                .insert((session.to_string(), year, day), stars + 1);
+            self.wrong.remove(session);
+            return Ok(Response::article(&format!(
+                "That's the right answer!  You are one gold star closer to saving Christmas. {back}"
+            )));
+        }
+        self.wrong.insert(session.to_string(), now);
+        let hint = match (answer.parse::<i64>(), correct.parse::<i64>()) {
+            (Ok(answer), Ok(correct)) if answer < correct => "  Your answer is too low.",
+            (Ok(answer), Ok(correct)) if answer > correct => "  Your answer is too high.",
+            _ => "",
+        };
+        Ok(Response::article(&format!(
+            "That's not the right answer.{hint}  Please wait one minute before trying again. {back}"
+        )))
+    }
+
+    /// Serves requests from `listener`, one connection at a time.
+    pub fn serve(&mut self, listener: &TcpListener) -> Result<()> {
+        for stream in listener.incoming() {
+            let stream = stream.context("accepting a connection")?;
+            if let Err(error) = self.connection(stream) {
+                eprintln!("warning: {error:#}");
+            }
+        }
+        Ok(())
+    }
+
+    fn connection(&mut self, stream: TcpStream) -> Result<()> {
+        stream
+            .set_read_timeout(Some(READ_TIMEOUT))
+            .context("setting the read timeout")?;
+        let mut reader = BufReader::new(&stream);
+        let request = match Request::read(&mut reader) {
+            Ok(Some(request)) => request,
+            Ok(None) => return Ok(()),
+            Err(error) => {
+                Response::text(400, format!("{error:#}\n")).write(&mut &stream)?;
+                return Err(error);
+            }
+        };
+        let response = self.respond(&request, Instant::now());
+        eprintln!("{} {} {}", request.method, request.path, response.status);
+        response.write(&mut &stream)
+    }
+}
+
+/// The page for a day with `stars`, or the closest page with fewer stars.
+fn page(dir: &Path, stars: u8) -> Result<String> {
+    for stars in (1..=stars).rev() {
+        match fs::read_to_string(dir.join(format!("puzzle-{stars}.html"))) {
+            Ok(page) => return Ok(page),
+            Err(error) if error.kind() == ErrorKind::NotFound => {}
+            Err(error) => return Err(error).context("reading the puzzle page"),
+        }
+    }
+    read(&dir.join("puzzle.html"))
+}
+
+fn read(path: &Path) -> Result<String> {
+    ensure!(
+        path.exists(),
+        "{} is missing from the fixtures",
+        path.display()
+    );
+    fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, io::Read, thread};
+
+    use super::*;
+
+    fn fixtures(name: &str) -> PathBuf {
+        let root = env::temp_dir().join(format!("aoc-site-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let day = root.join("2022/1");
+        fs::create_dir_all(&day).unwrap();
+        fs::write(day.join("puzzle.html"), "part one\n").unwrap();
+        fs::write(day.join("puzzle-2.html"), "both parts\n").unwrap();
+        fs::write(day.join("input"), "1000\n2000\n").unwrap();
+        fs::write(day.join(status::LEDGER), "one 3000\ntwo 6000\n").unwrap();
+        root
+    }
+
+    fn request(method: &str, path: &str, session: Option<&str>, body: &str) -> Request {
+        let mut headers = Vec::new();
+        if let Some(session) = session {
+            headers.push(("cookie".into(), format!("theme=dark; session={session}")));
+        }
+        Request {
+            method: method.into(),
+            path: path.into(),
+            headers,
+            body: body.into(),
+        }
+    }
+
+    fn answer(site: &mut Site, level: u8, answer: &str, now: Instant) -> String {
+        let body = format!("level={level}&answer={answer}");
+        let request = request("POST", "/2022/day/1/answer", Some("abc"), &body);
+        let response = site.respond(&request, now);
+        assert_eq!(response.status, 200);
+        response.body
+    }
+
+    #[test]
+    fn respond() {
+        let root = fixtures("respond");
+        let mut site = Site::new(&root);
+        let now = Instant::now();
+        let get =
+            |site: &mut Site, path, session| site.respond(&request("GET", path, session, ""), now);
+
+        let page = get(&mut site, "/2022/day/1", None);
+        assert_eq!(page.body, "part one\n");
+        let mut cached = request("GET", "/2022/day/1", None, "");
+        cached
+            .headers
+            .push(("if-none-match".into(), page.etag.clone().unwrap()));
+        let unchanged = site.respond(&cached, now);
+        assert_eq!((unchanged.status, unchanged.body.as_str()), (304, ""));
+        assert_eq!(get(&mut site, "/2022/day/1/input", None).status, 400);
+        let input = get(&mut site, "/2022/day/1/input", Some("abc"));
+        assert_eq!((input.status, input.body.as_str()), (200, "1000\n2000\n"));
+        assert_eq!(
+            get(&mut site, "/2022/day/2", Some("abc")).body,
+            NOT_UNLOCKED
+        );
+        assert_eq!(get(&mut site, "/2022/day/x", Some("abc")).status, 404);
+
+        assert!(answer(&mut site, 2, "6000", now).contains("right level"));
+        assert!(answer(&mut site, 1, "2000", now)
+            .contains("not the right answer.  Your answer is too low."));
+        let later = now + Duration::from_secs(20);
+        assert!(answer(&mut site, 1, "3000", later).contains("You have 40s left to wait."));
+        let later = now + site.cooldown;
+        assert!(answer(&mut site, 1, "3000", later).contains("That's the right answer!"));
+        assert!(answer(&mut site, 1, "3000", later).contains("right level"));
+        assert_eq!(
+            get(&mut site, "/2022/day/1", Some("abc")).body,
+            "part one\n"
+        );
+        assert!(answer(&mut site, 2, "6000", later).contains("That's the right answer!"));
+        assert_eq!(
+            get(&mut site, "/2022/day/1", Some("abc")).body,
+            "both parts\n"
+        );
+        cached.headers.push(("cookie".into(), "session=abc".into()));
+        assert_eq!(site.respond(&cached, now).status, 200);
+        assert_eq!(
+            get(&mut site, "/2022/day/1", Some("other")).body,
+            "part one\n"
+        );
+        assert!(answer(&mut site, 2, "6000", later).contains("right level"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn read() {
+        let mut raw = "GET / HTTP/1.1\r\nHost: x\r\n\r\n".as_bytes();
+        let request = Request::read(&mut raw).unwrap().unwrap();
+        assert_eq!(request.header("host"), Some("x"));
+        assert_eq!(Request::read(&mut "".as_bytes()).unwrap(), None);
+        let long = format!("GET / HTTP/1.1\r\nCookie: {}\r\n\r\n", "a".repeat(10_000));
+        let error = Request::read(&mut long.as_bytes()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "request head is over the limit of 8192 bytes"
+        );
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(super::decode("RFF%2BFW+B%"), "RFF+FW B%");
+    }
+
+    #[test]
+    fn serve() {
+        let root = fixtures("serve");
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut site = Site::new(&root);
+        thread::spawn(move || site.serve(&listener));
+
+        let send = |request: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let input = send("GET /2022/day/1/input HTTP/1.1\r\nCookie: session=abc\r\n\r\n");
+        assert!(input.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(input.ends_with("\r\n\r\n1000\n2000\n"));
+        let body = "level=1&answer=3000";
+        let reply = send(&format!(
+            "POST /2022/day/1/answer HTTP/1.1\r\nCookie: session=abc\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        ));
+        assert!(reply.contains("That's the right answer!"));
+        let huge =
+            send("POST /2022/day/1/answer HTTP/1.1\r\nContent-Length: 1000000000000\r\n\r\n");
+        assert!(huge.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(huge.ends_with("content length 1000000000000 is over the limit of 65536 bytes\n"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,415 @@
+//! An overview of every day in the repository, built only from files on
+//! disk: the stars from each `README.md` or `answers` ledger and what the day
+//! last recorded in its `target/aoc-status`.
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Write as _},
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Error, Result};
+
+use crate::{readme::Readme, runner::Part};
+
+/// Where a day keeps its [`Record`], relative to the day's directory.
+pub const RECORD: &str = "target/aoc-status";
+
+/// The ledger of accepted answers, relative to the day's directory, for days
+/// whose `README.md` was downloaded before they were solved.
+pub const LEDGER: &str = "answers";
+
+/// Reads the ledger of the day in `dir`, with lines like `one 72718`.
+pub fn ledger(dir: &Path) -> Result<[Option<String>; 2]> {
+    let path = dir.join(LEDGER);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok([None, None]),
+        Err(error) => return Err(error).with_context(|| format!("reading {}", path.display())),
+    };
+    let mut answers = [None, None];
+    for (idx, line) in text.lines().enumerate() {
+        let (part, answer) = line.split_once(' ').with_context(|| {
+            format!("{}:{}: expected `<part> <answer>`", path.display(), idx + 1)
+        })?;
+        let part = match part {
+            "one" => Part::One,
+            "two" => Part::Two,
+            other => bail!("{}:{}: unknown part {other:?}", path.display(), idx + 1),
+        };
+        answers[part as usize] = Some(answer.to_string());
+    }
+    Ok(answers)
+}
+
+fn readme(dir: &Path) -> Result<Option<Readme>> {
+    let path = dir.join("README.md");
+    match fs::read_to_string(&path) {
+        Ok(text) => text
+            .parse()
+            .map(Some)
+            .with_context(|| format!("parsing {}", path.display())),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error).with_context(|| format!("reading {}", path.display())),
+    }
+}
+
+/// The answers given in `readme`, falling back to the ledger in `dir`.
+fn recorded(readme: Option<&Readme>, dir: &Path) -> Result<[Option<String>; 2]> {
+    let mut answers = ledger(dir)?;
+    for part in [Part::One, Part::Two] {
+        if let Some(answer) = readme
+            .and_then(|readme| readme.part(part))
+            .and_then(|description| description.answer.clone())
+        {
+            answers[part as usize] = Some(answer);
+        }
+    }
+    Ok(answers)
+}
+
+/// The accepted answers of the day in `dir`, from its `README.md` or its
+/// ledger.
+pub fn answers(dir: &Path) -> Result<[Option<String>; 2]> {
+    recorded(readme(dir)?.as_ref(), dir)
+}
+
+/// The last runtime of each part and the last test result of a day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Record {
+    pub runtimes: [Option<Duration>; 2],
+    pub tests: Option<bool>,
+}
+
+impl Record {
+    /// Loads the record of the day in `dir`, which is empty if the day never
+    /// recorded anything.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(RECORD);
+        match fs::read_to_string(&path) {
+            Ok(text) => text
+                .parse()
+                .with_context(|| format!("reading {}", path.display())),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error).with_context(|| format!("reading {}", path.display())),
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(RECORD);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("creating {}", parent.display()))?;
+        }
+        fs::write(&path, self.to_string()).with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Loads the record of the day in `dir`, changes it with `update` and
+    /// saves it again.
+    pub fn update(dir: &Path, update: impl FnOnce(&mut Self)) -> Result<()> {
+        let mut record = Self::load(dir)?;
+        update(&mut record);
+        record.save(dir)
+    }
+
+    pub fn runtime(&self, part: Part) -> Option<Duration> {
+        self.runtimes[part as usize]
+    }
+
+    pub fn set_runtime(&mut self, part: Part, runtime: Duration) {
+        self.runtimes[part as usize] = Some(runtime);
+    }
+}
+
+impl FromStr for Record {
+    type Err = Error;
+
+    /// Parses `<key> <value>` lines, e.g. `one 1500000` for a runtime in
+    /// nanoseconds or `tests passed`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut record = Self::default();
+        for (idx, line) in s.lines().enumerate() {
+            let line_number = idx + 1;
+            let (key, value) = line
+                .split_once(' ')
+                .with_context(|| format!("line {line_number}: expected `<key> <value>`"))?;
+            let nanos = || {
+                value
+                    .parse()
+                    .map(Duration::from_nanos)
+                    .with_context(|| format!("line {line_number}: invalid runtime {value:?}"))
+            };
+            match key {
+                "one" => record.runtimes[0] = Some(nanos()?),
+                "two" => record.runtimes[1] = Some(nanos()?),
+                "tests" => {
+                    record.tests = Some(match value {
+                        "passed" => true,
+                        "failed" => false,
+                        other => bail!("line {line_number}: invalid test result {other:?}"),
+                    })
+                }
+                other => bail!("line {line_number}: unknown key {other:?}"),
+            }
+        }
+        Ok(record)
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, runtime) in ["one", "two"].iter().zip(self.runtimes) {
+            if let Some(runtime) = runtime {
+                writeln!(f, "{key} {}", runtime.as_nanos())?;
+            }
+        }
+        if let Some(passed) = self.tests {
+            writeln!(f, "tests {}", if passed { "passed" } else { "failed" })?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything known about one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub dir: PathBuf,
+    /// `None` if the puzzle was never downloaded.
+    pub title: Option<String>,
+    pub stars: usize,
+    pub record: Record,
+    /// Why the files of the day could not be read, if they could not.
+    pub problem: Option<String>,
+}
+
+/// Numbered directories in `dir`, sorted by their number.
+fn numbered<T: FromStr + Ord>(dir: &Path) -> Result<BTreeMap<T, PathBuf>> {
+    let entries = fs::read_dir(dir).with_context(|| format!("listing {}", dir.display()))?;
+    let mut numbered = BTreeMap::new();
+    for entry in entries {
+        let entry = entry.with_context(|| format!("listing {}", dir.display()))?;
+        if let Ok(number) = entry.file_name().to_string_lossy().parse() {
+            if entry.path().is_dir() {
+                numbered.insert(number, entry.path());
+            }
+        }
+    }
+    Ok(numbered)
+}
+
+/// Finds every day crate below `root`, in `<year>/<day>` directories.
+///
+/// Days whose files cannot be read are still listed, with the
+/// [`problem`](Day::problem).
+pub fn scan(root: &Path) -> Result<Vec<Day>> {
+    let mut days = Vec::new();
+    for (year, year_dir) in numbered::<u16>(root)? {
+        for (day, dir) in numbered::<u8>(&year_dir)? {
+            if !dir.join("Cargo.toml").exists() {
+                continue;
+            }
+            let mut problems = Vec::new();
+            let (title, stars) = match readme(&dir).and_then(|readme| {
+                let stars = recorded(readme.as_ref(), &dir)?.iter().flatten().count();
+                Ok((readme.map(|readme| readme.title), stars))
+            }) {
+                Ok(found) => found,
+                Err(error) => {
+                    problems.push(format!("{error:#}"));
+                    (None, 0)
+                }
+            };
+            let record = Record::load(&dir).unwrap_or_else(|error| {
+                problems.push(format!("{error:#}"));
+                Record::default()
+            });
+            days.push(Day {
+                year,
+                day,
+                title,
+                stars,
+                record,
+                problem: (!problems.is_empty()).then(|| problems.join("; ")),
+                dir,
+            });
+        }
+    }
+    Ok(days)
+}
+
+/// Runs the tests of `day`, recording whether they passed.
+pub fn test(day: &mut Day) -> Result<()> {
+    let status = Command::new("cargo")
+        .args(["test", "--quiet"])
+        .current_dir(&day.dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("running the tests in {}", day.dir.display()))?;
+    day.record.tests = Some(status.success());
+    day.record.save(&day.dir)
+}
+
+fn stars(stars: usize) -> &'static str {
+    match stars {
+        0 => "  ",
+        1 => "* ",
+        _ => "**",
+    }
+}
+
+fn runtime(runtime: Option<Duration>) -> String {
+    runtime.map_or_else(|| "-".into(), |runtime| format!("{runtime:.1?}"))
+}
+
+/// Draws a calendar of the stars for every year in `days`, followed by a
+/// table of the days.
+pub fn render(days: &[Day]) -> String {
+    let mut years = BTreeMap::<u16, Vec<&Day>>::new();
+    for day in days {
+        years.entry(day.year).or_default().push(day);
+    }
+    let mut out = String::new();
+    for (year, days) in years {
+        let total: usize = days.iter().map(|day| day.stars).sum();
+        let _ = writeln!(out, "{year}  {total}/50 stars");
+        for week in 0..5 {
+            let row: Vec<_> = (1..=5)
+                .map(|weekday| {
+                    let number = week * 5 + weekday;
+                    match days.iter().find(|day| day.day == number) {
+                        Some(day) if day.problem.is_some() => format!("{number:>2} !!"),
+                        Some(day) => format!("{number:>2} {}", stars(day.stars)),
+                        None => format!("{number:>2} .."),
+                    }
+                })
+                .collect();
+            let _ = writeln!(out, "  {}", row.join("   ").trim_end());
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "  day  stars  tests   part one   part two  title");
+        for day in days {
+            let tests = match day.record.tests {
+                Some(true) => "ok",
+                Some(false) => "FAIL",
+                None => "-",
+            };
+            let title = match (&day.problem, &day.title) {
+                (Some(problem), _) => format!("error: {problem}"),
+                (None, Some(title)) => title.clone(),
+                (None, None) => "-".into(),
+            };
+            let _ = writeln!(
+                out,
+                "  {:>3}  {:<5}  {tests:<5}  {:>9}  {:>9}  {title}",
+                day.day,
+                stars(day.stars),
+                runtime(day.record.runtime(Part::One)),
+                runtime(day.record.runtime(Part::Two)),
+            );
+        }
+        let _ = writeln!(out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn record() {
+        let record: Record = "one 1500000\ntests failed\n".parse().unwrap();
+        assert_eq!(record.runtime(Part::One), Some(Duration::from_micros(1500)));
+        assert_eq!(record.runtime(Part::Two), None);
+        assert_eq!(record.tests, Some(false));
+        assert_eq!(record.to_string().parse::<Record>().unwrap(), record);
+        assert!("one fast\n".parse::<Record>().is_err());
+        let error = "three 1\n".parse::<Record>().unwrap_err();
+        assert_eq!(error.to_string(), "line 1: unknown key \"three\"");
+    }
+
+    #[test]
+    fn render() {
+        let day = |day, stars, title: Option<&str>| Day {
+            year: 2022,
+            day,
+            dir: PathBuf::new(),
+            title: title.map(Into::into),
+            stars,
+            record: Record::default(),
+            problem: None,
+        };
+        let mut first = day(1, 2, Some("Calorie Counting"));
+        first
+            .record
+            .set_runtime(Part::One, Duration::from_micros(1500));
+        first.record.tests = Some(true);
+        let mut broken = day(3, 0, None);
+        broken.problem = Some("reading answers: denied".into());
+        let days = [first, day(2, 1, None), broken];
+        assert_eq!(
+            super::render(&days),
+            indoc! {"
+                2022  3/50 stars
+                   1 **    2 *     3 !!    4 ..    5 ..
+                   6 ..    7 ..    8 ..    9 ..   10 ..
+                  11 ..   12 ..   13 ..   14 ..   15 ..
+                  16 ..   17 ..   18 ..   19 ..   20 ..
+                  21 ..   22 ..   23 ..   24 ..   25 ..
+
+                  day  stars  tests   part one   part two  title
+                    1  **     ok         1.5ms          -  Calorie Counting
+                    2  *      -              -          -  -
+                    3         -              -          -  error: reading answers: denied
+
+            "}
+        );
+    }
+
+    #[test]
+    fn scan() {
+        let root = env::temp_dir().join(format!("aoc-status-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let day = root.join("2022/01");
+        fs::create_dir_all(&day).unwrap();
+        fs::create_dir_all(root.join("2022/template")).unwrap();
+        fs::write(day.join("Cargo.toml"), "").unwrap();
+        fs::write(
+            day.join("README.md"),
+            "\\--- Day 1: Calorie Counting ---\n\nYour puzzle answer was `1`.\n",
+        )
+        .unwrap();
+        Record::update(&day, |record| record.tests = Some(true)).unwrap();
+        let other = root.join("2022/02");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("Cargo.toml"), "").unwrap();
+        fs::write(other.join(LEDGER), "one 15\ntwo 12\n").unwrap();
+
+        let days = super::scan(&root).unwrap();
+        assert_eq!(days.len(), 2);
+        assert_eq!((days[0].year, days[0].day, days[0].stars), (2022, 1, 1));
+        assert_eq!(days[0].title.as_deref(), Some("Calorie Counting"));
+        assert_eq!(days[0].record.tests, Some(true));
+        assert_eq!(
+            (days[1].day, days[1].stars, days[1].title.as_deref()),
+            (2, 2, None)
+        );
+        fs::write(other.join(LEDGER), "three 1\n").unwrap();
+        let days = super::scan(&root).unwrap();
+        assert_eq!(days[0].problem, None);
+        let problem = days[1].problem.as_deref().unwrap();
+        assert!(problem.ends_with("answers:1: unknown part \"three\""));
+        assert_eq!(days[1].stars, 0);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
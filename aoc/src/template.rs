@@ -0,0 +1,235 @@
+//! Creates the crate for a new day from one of the skeletons in `template/`.
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{bail, ensure, Context, Error, Result};
+
+/// The shape of a puzzle, deciding which parser and tests are pre-wired.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Skeleton {
+    /// Only the runner, for puzzles that fit none of the others.
+    #[default]
+    Basic,
+    /// A rectangle of characters parsed into a [`Grid`](crate::grid::Grid).
+    Grid,
+    /// One item per line, parsed with a [`Cursor`](crate::parse::Cursor).
+    Lines,
+    /// Items spanning several lines, separated by blank lines.
+    Blocks,
+    /// A state that is stepped until it is done, with a `--step` flag.
+    Simulation,
+}
+
+impl Skeleton {
+    pub const ALL: [Self; 5] = [
+        Self::Basic,
+        Self::Grid,
+        Self::Lines,
+        Self::Blocks,
+        Self::Simulation,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Basic => "basic",
+            Self::Grid => "grid",
+            Self::Lines => "lines",
+            Self::Blocks => "blocks",
+            Self::Simulation => "simulation",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Self::Basic => include_str!("../../template/skeletons/basic.rs"),
+            Self::Grid => include_str!("../../template/skeletons/grid.rs"),
+            Self::Lines => include_str!("../../template/skeletons/lines.rs"),
+            Self::Blocks => include_str!("../../template/skeletons/blocks.rs"),
+            Self::Simulation => include_str!("../../template/skeletons/simulation.rs"),
+        }
+    }
+}
+
+impl FromStr for Skeleton {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::ALL.into_iter().find(|skeleton| skeleton.name() == s) {
+            Some(skeleton) => Ok(skeleton),
+            None => {
+                let names: Vec<_> = Self::ALL.iter().map(|skeleton| skeleton.name()).collect();
+                bail!(
+                    "unknown skeleton {s:?}, expected one of {}",
+                    names.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl Display for Skeleton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A puzzle, identified by its year and day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl Puzzle {
+    pub fn new(year: u16, day: u8) -> Result<Self> {
+        ensure!(year >= 2015, "there is no Advent of Code {year}");
+        ensure!((1..=25).contains(&day), "there is no day {day}");
+        Ok(Self { year, day })
+    }
+
+    /// The name of the day's crate and fuzz target, e.g. `y2022d7`.
+    pub fn name(self) -> String {
+        format!("y{}d{}", self.year, self.day)
+    }
+
+    /// The day's directory, relative to the repository root.
+    pub fn dir(self) -> PathBuf {
+        [self.year.to_string(), self.day.to_string()]
+            .iter()
+            .collect()
+    }
+
+    /// Replaces the `{year}` and `{day}` placeholders in `text`.
+    pub fn render(self, text: &str) -> String {
+        text.replace("{year}", &self.year.to_string())
+            .replace("{day}", &self.day.to_string())
+    }
+}
+
+/// The files of a new day, relative to its directory.
+pub fn files(puzzle: Puzzle, skeleton: Skeleton) -> [(&'static str, String); 3] {
+    [
+        (
+            "Cargo.toml",
+            puzzle.render(include_str!("../../template/Cargo.toml")),
+        ),
+        ("src/main.rs", puzzle.render(skeleton.source())),
+        (
+            "submit",
+            puzzle.render(include_str!("../../template/submit")),
+        ),
+    ]
+}
+
+/// Writes the crate for `puzzle` below `root` and registers its fuzz target,
+/// returning the day's directory.
+///
+/// Files already in the directory, like a downloaded `README.md` and `input`,
+/// are kept, but an existing crate is never overwritten.
+pub fn create(root: &Path, puzzle: Puzzle, skeleton: Skeleton) -> Result<PathBuf> {
+    let dir = root.join(puzzle.dir());
+    let main = dir.join("src/main.rs");
+    ensure!(!main.exists(), "{} already exists", main.display());
+    for (name, contents) in files(puzzle, skeleton) {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap_or(&dir))
+            .with_context(|| format!("creating {}", dir.display()))?;
+        fs::write(&path, contents).with_context(|| format!("writing {}", path.display()))?;
+    }
+    make_executable(&dir.join("submit"))?;
+    let fuzz = root.join("fuzz");
+    if fuzz.is_dir() {
+        register_fuzz_target(&fuzz, puzzle)?;
+    }
+    Ok(dir)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("making {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_: &Path) -> Result<()> {
+    Ok(())
+}
+
+/// Adds the fuzz target for `puzzle` to the fuzz crate in `fuzz`, unless it
+/// is there already.
+fn register_fuzz_target(fuzz: &Path, puzzle: Puzzle) -> Result<()> {
+    let name = puzzle.name();
+    let manifest = fuzz.join("Cargo.toml");
+    let mut contents =
+        fs::read_to_string(&manifest).with_context(|| format!("reading {}", manifest.display()))?;
+    if contents.contains(&format!("name = \"{name}\"")) {
+        return Ok(());
+    }
+    contents += &format!(
+        "\n[[bin]]\nname = \"{name}\"\npath = \"fuzz_targets/{name}.rs\"\ntest = false\ndoc = false\nbench = false\n"
+    );
+    fs::write(&manifest, contents).with_context(|| format!("writing {}", manifest.display()))?;
+
+    let target = fuzz.join("fuzz_targets").join(format!("{name}.rs"));
+    let dir = puzzle.dir();
+    let dir = dir.to_string_lossy().replace('\\', "/");
+    let source = format!(
+        "#![no_main]\nuse libfuzzer_sys::fuzz_target;\n\n#[path = \"../../{dir}/src/main.rs\"]\n#[allow(dead_code)]\nmod day;\n\nfuzz_target!(|input: &str| aoc::fuzz(input, day::validate, day::one, day::two));\n"
+    );
+    fs::write(&target, source).with_context(|| format!("writing {}", target.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn skeletons() {
+        for skeleton in Skeleton::ALL {
+            assert_eq!(skeleton.name().parse::<Skeleton>().unwrap(), skeleton);
+            let puzzle = Puzzle::new(2022, 13).unwrap();
+            let [(_, manifest), (_, main), _] = files(puzzle, skeleton);
+            assert!(manifest.contains("name = \"y2022d13\""));
+            assert!(!main.contains("{year}") && !main.contains("{day}"));
+            assert!(main.contains("fn one(") && main.contains("fn two("));
+        }
+        let error = "maze".parse::<Skeleton>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "unknown skeleton \"maze\", expected one of basic, grid, lines, blocks, simulation"
+        );
+        assert!(Puzzle::new(2022, 26).is_err());
+    }
+
+    #[test]
+    fn create() {
+        let root = env::temp_dir().join(format!("aoc-template-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("2022/3")).unwrap();
+        fs::write(root.join("2022/3/input"), "keep\n").unwrap();
+        fs::create_dir_all(root.join("fuzz/fuzz_targets")).unwrap();
+        fs::write(root.join("fuzz/Cargo.toml"), "[package]\n").unwrap();
+
+        let puzzle = Puzzle::new(2022, 3).unwrap();
+        let dir = super::create(&root, puzzle, Skeleton::Grid).unwrap();
+        assert_eq!(dir, root.join("2022/3"));
+        assert_eq!(fs::read_to_string(dir.join("input")).unwrap(), "keep\n");
+        let main = fs::read_to_string(dir.join("src/main.rs")).unwrap();
+        assert!(main.contains("Grid::parse"));
+        assert!(super::create(&root, puzzle, Skeleton::Basic).is_err());
+
+        let manifest = fs::read_to_string(root.join("fuzz/Cargo.toml")).unwrap();
+        assert_eq!(manifest.matches("name = \"y2022d3\"").count(), 1);
+        let target = fs::read_to_string(root.join("fuzz/fuzz_targets/y2022d3.rs")).unwrap();
+        assert!(target.contains("#[path = \"../../2022/3/src/main.rs\"]"));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+//! Keeping track of the largest values seen.
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// The `n` largest values pushed so far.
+///
+/// Backed by a min-heap of size `n`, so pushing is `O(log n)` and memory does
+/// not grow with the number of values pushed.
+#[derive(Debug, Clone)]
+pub struct TopN<T> {
+    n: usize,
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> TopN<T> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            heap: BinaryHeap::with_capacity(n + 1),
+        }
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.heap.len() < self.n {
+            self.heap.push(Reverse(value));
+        } else if self
+            .heap
+            .peek()
+            .is_some_and(|Reverse(smallest)| *smallest < value)
+        {
+            self.heap.pop();
+            self.heap.push(Reverse(value));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The kept values, largest first.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(value)| value)
+            .collect()
+    }
+}
+
+impl<T: Ord> Extend<T> for TopN<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: Ord> IntoIterator for TopN<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    /// Iterates the kept values, largest first.
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_sorted_vec().into_iter()
+    }
+}
+
+/// The `n` largest values of `iter`, largest first.
+pub fn top_n<T: Ord>(iter: impl IntoIterator<Item = T>, n: usize) -> Vec<T> {
+    let mut top = TopN::new(n);
+    top.extend(iter);
+    top.into_sorted_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn top() {
+        assert_eq!(top_n([5, 1, 9, 3, 9, 7], 3), [9, 9, 7]);
+        assert_eq!(top_n([2, 1], 3), [2, 1]);
+        assert!(top_n([2, 1], 0).is_empty());
+        let mut top = TopN::new(2);
+        top.extend(["b", "d", "a", "c"]);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top.into_iter().collect::<Vec<_>>(), ["d", "c"]);
+    }
+}
@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../2021/01/src/main.rs"]
+#[allow(dead_code)]
+mod day;
+
+fuzz_target!(|input: &str| aoc::fuzz(input, day::validate, day::one, day::two));
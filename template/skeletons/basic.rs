@@ -0,0 +1,55 @@
+#![doc = include_str!("../README.md")]
+use anyhow::Result;
+use aoc::{
+    answer::Answer,
+    check::Problem,
+    generate::{Random, Rng},
+    runner::Runner,
+};
+
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    todo!("Check the input")
+}
+
+fn generate(rng: &mut Random, size: usize) -> String {
+    todo!("Generate a random input")
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    todo!("Do part one")
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    todo!("Do part two")
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
+}
@@ -0,0 +1,81 @@
+#![doc = include_str!("../README.md")]
+use anyhow::Result;
+use aoc::{
+    answer::Answer,
+    check::{self, Problem},
+    generate::{Random, Rng},
+    parse::{self, Cursor},
+    runner::Runner,
+};
+
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+/// A block of lines, separated from the next one by a blank line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Block {}
+
+impl Block {
+    fn parse(cursor: &mut Cursor) -> parse::Result<Self> {
+        todo!("Parse {:?}", cursor.rest())
+    }
+}
+
+fn parse(input: &str) -> parse::Result<Vec<Block>> {
+    parse::parse(input, |cursor| cursor.blocks(Block::parse))
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    check::blocks(input, |block| parse::parse(block, Block::parse).map(drop))
+}
+
+/// `size` blocks.
+fn generate(rng: &mut Random, size: usize) -> String {
+    let blocks: Vec<String> = (0..size)
+        .map(|_| todo!("Generate a random block"))
+        .collect();
+    blocks.join("\n")
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    let blocks = parse(input)?;
+    todo!("Do part one")
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    let blocks = parse(input)?;
+    todo!("Do part two")
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(super::validate(INPUT), []);
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
+}
@@ -0,0 +1,75 @@
+#![doc = include_str!("../README.md")]
+use anyhow::Result;
+use aoc::{
+    answer::Answer,
+    check::Problem,
+    generate::{Random, Rng},
+    grid::Grid,
+    parse,
+    runner::Runner,
+};
+
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    Runner::from_env()?
+        .generator(generate)
+        .run(INPUT, validate, one, two)
+}
+
+/// The cell a character of the input stands for, `None` if it is invalid.
+fn cell(c: char) -> Option<char> {
+    todo!("Convert {c:?} into a cell")
+}
+
+fn parse(input: &str) -> parse::Result<Grid<char>> {
+    Grid::parse(input, cell)
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    parse(input).err().into_iter().map(Problem::from).collect()
+}
+
+/// A grid of `size` by `size` cells.
+fn generate(rng: &mut Random, size: usize) -> String {
+    Grid::from_fn(size, size, |_| -> char { todo!("Generate a random cell") }).to_string()
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    let grid = parse(input)?;
+    todo!("Do part one")
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    let grid = parse(input)?;
+    todo!("Do part two")
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn parse() {
+        let grid = super::parse(INPUT).unwrap();
+        assert_eq!(grid.to_string(), INPUT);
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
+}
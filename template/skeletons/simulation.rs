@@ -0,0 +1,123 @@
+#![doc = include_str!("../README.md")]
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use anyhow::{Error, Result};
+use aoc::{
+    answer::Answer,
+    check::Problem,
+    generate::{Random, Rng},
+    runner::{Part, Runner},
+};
+
+const INPUT: &str = include_str!("../input");
+
+fn main() -> Result<()> {
+    let mut runner = Runner::from_env()?;
+
+    if runner.flag("--step") && !runner.check_input {
+        runner.finish()?;
+        let part = runner.part;
+        eprintln!("=== Solving Part {part:?} ===");
+        let state = simulate(INPUT, part, |state| eprintln!("{state}"))?;
+        println!("{}", state.answer()?);
+        return Ok(());
+    }
+
+    runner.generator(generate).run(INPUT, validate, one, two)
+}
+
+/// Everything that changes while the simulation runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct State {}
+
+impl State {
+    /// Advances the simulation by one step, returning whether it is still
+    /// running.
+    fn step(&mut self, part: Part) -> Result<bool> {
+        todo!("Advance the simulation of part {part:?}")
+    }
+
+    /// The puzzle answer, read off the final state.
+    fn answer(&self) -> Result<Answer> {
+        todo!("Read the answer")
+    }
+}
+
+impl FromStr for State {
+    type Err = Error;
+
+    /// Parses the initial state.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        todo!("Parse {s:?}")
+    }
+}
+
+impl Display for State {
+    /// Draws the state for `--step`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        todo!("Draw the state")
+    }
+}
+
+/// Runs the simulation to its end, calling `on_step` after every step.
+fn simulate(input: &str, part: Part, mut on_step: impl FnMut(&State)) -> Result<State> {
+    let mut state: State = input.parse()?;
+    while state.step(part)? {
+        on_step(&state);
+    }
+    Ok(state)
+}
+
+pub fn validate(input: &str) -> Vec<Problem> {
+    match State::from_str(input) {
+        Ok(_) => Vec::new(),
+        Err(error) => vec![Problem::new(None, format_args!("{error:#}"))],
+    }
+}
+
+fn generate(rng: &mut Random, size: usize) -> String {
+    todo!("Generate a random initial state")
+}
+
+pub fn one(input: &str) -> Result<Answer> {
+    simulate(input, Part::One, |_| {})?.answer()
+}
+
+pub fn two(input: &str) -> Result<Answer> {
+    simulate(input, Part::Two, |_| {})?.answer()
+}
+
+#[cfg(test)]
+mod test {
+    use aoc::runner::Part;
+    use indoc::indoc;
+
+    const INPUT: &str = indoc! {r#"
+
+    "#};
+
+    #[test]
+    fn one() {
+        let output = "";
+        assert_eq!(super::one(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn two() {
+        let output = "";
+        assert_eq!(super::two(INPUT).unwrap().to_string(), output);
+    }
+
+    #[test]
+    fn steps() {
+        let mut steps = Vec::new();
+        super::simulate(INPUT, Part::One, |state| steps.push(state.to_string())).unwrap();
+        let expected: [&str; 0] = [];
+        assert_eq!(steps, expected);
+    }
+
+    aoc::stress_test!(super::generate, super::validate, super::one, super::two);
+}